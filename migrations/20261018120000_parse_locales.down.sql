@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE guilds DROP COLUMN parse_locales;
@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE guilds ADD COLUMN parse_locales TEXT NOT NULL DEFAULT 'nl,en';
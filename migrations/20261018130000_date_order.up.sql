@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE guilds ADD COLUMN date_order TEXT NOT NULL DEFAULT 'dmy';
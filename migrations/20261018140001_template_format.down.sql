@@ -0,0 +1,5 @@
+-- Add down migration script here
+-- Templates can't be converted back in general, only the default is restored
+UPDATE guilds SET format = '$(Good luck with your exam!)#(Good luck with $name!)'
+    WHERE format = '{if name}Good luck with {name}!{else}Good luck with your exam!{end}'
+        OR format = '{if !name}Good luck with your exam!{end}{if name}Good luck with {name}!{end}';
@@ -0,0 +1,11 @@
+-- Add up migration script here
+-- Convert the old $(...)/#(...)/$name formats to the template language:
+-- escape literal braces first, then rewrite the groups and the variable
+UPDATE guilds SET format =
+    regexp_replace(
+        regexp_replace(
+            regexp_replace(
+                replace(replace(format, '{', '{{'), '}', '}}'),
+                '#\(([^)]*)\)', '{if name}\1{end}', 'g'),
+            '\$\(([^)]*)\)', '{if !name}\1{end}', 'g'),
+        '\$name', '{name}', 'g');
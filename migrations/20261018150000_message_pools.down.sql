@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE last_messages;
+DROP TABLE messages;
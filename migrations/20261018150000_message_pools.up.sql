@@ -0,0 +1,23 @@
+-- Add up migration script here
+CREATE TABLE messages (
+    message_id BIGSERIAL PRIMARY KEY,
+    guild_id INT8 NOT NULL,
+    pool TEXT NOT NULL,
+    format TEXT NOT NULL,
+    weight INT4 NOT NULL DEFAULT 1,
+    CONSTRAINT weight_check check (weight > 0),
+    FOREIGN KEY (guild_id)
+        REFERENCES guilds (guild_id)
+            ON DELETE CASCADE
+);
+
+-- The message a user got last, so they don't get the same one twice in a row
+CREATE TABLE last_messages (
+    guild_id INT8 NOT NULL,
+    user_id INT8 NOT NULL,
+    message_id INT8 NOT NULL,
+    PRIMARY KEY (guild_id, user_id),
+    FOREIGN KEY (message_id)
+        REFERENCES messages (message_id)
+            ON DELETE CASCADE
+);
@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE guilds DROP COLUMN embed_image;
+ALTER TABLE guilds DROP COLUMN embed_thumbnail;
+ALTER TABLE guilds DROP COLUMN embed_colour;
+ALTER TABLE guilds DROP COLUMN embed_title;
+ALTER TABLE guilds DROP COLUMN delivery;
@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE guilds ADD COLUMN delivery TEXT NOT NULL DEFAULT 'text';
+ALTER TABLE guilds ADD COLUMN embed_title TEXT;
+ALTER TABLE guilds ADD COLUMN embed_colour INT4;
+ALTER TABLE guilds ADD COLUMN embed_thumbnail TEXT;
+ALTER TABLE guilds ADD COLUMN embed_image TEXT;
@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE guilds DROP COLUMN language;
+UPDATE guilds SET format = '{if name}Good luck with {name}!{else}Good luck with your exam!{end}'
+    WHERE format IS NULL;
+ALTER TABLE guilds ALTER COLUMN format SET NOT NULL;
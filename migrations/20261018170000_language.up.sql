@@ -0,0 +1,8 @@
+-- Add up migration script here
+-- Guilds without their own format get the default format in their language,
+-- including the old default as converted by the template migration
+ALTER TABLE guilds ALTER COLUMN format DROP NOT NULL;
+UPDATE guilds SET format = NULL
+    WHERE format = '{if name}Good luck with {name}!{else}Good luck with your exam!{end}'
+        OR format = '{if !name}Good luck with your exam!{end}{if name}Good luck with {name}!{end}';
+ALTER TABLE guilds ADD COLUMN language TEXT;
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE exams DROP COLUMN message;
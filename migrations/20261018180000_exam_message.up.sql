@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE exams ADD COLUMN message TEXT;
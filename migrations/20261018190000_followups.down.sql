@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE exam_results;
+ALTER TABLE exams DROP COLUMN reminded;
+ALTER TABLE guilds DROP COLUMN followup_format;
+ALTER TABLE guilds DROP COLUMN followup_time;
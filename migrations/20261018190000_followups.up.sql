@@ -0,0 +1,20 @@
+-- Add up migration script here
+-- Follow-ups are sent on the day of the exam, NULL time means they're off
+ALTER TABLE guilds ADD COLUMN followup_time TIME;
+ALTER TABLE guilds ADD COLUMN followup_format TEXT;
+
+-- Exams are kept until their follow-up is sent
+ALTER TABLE exams ADD COLUMN reminded BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- The exam is deleted once the follow-up is sent, so it's copied here
+CREATE TABLE exam_results (
+    exam_id INT8 PRIMARY KEY,
+    guild_id INT8 NOT NULL,
+    user_id INT8 NOT NULL,
+    day DATE NOT NULL,
+    exam_name TEXT NOT NULL,
+    result TEXT,
+    FOREIGN KEY (guild_id)
+        REFERENCES guilds (guild_id)
+            ON DELETE CASCADE
+);
@@ -0,0 +1,12 @@
+-- Add down migration script here
+DELETE FROM exam_results a USING exam_results b
+    WHERE a.exam_id = b.exam_id AND a.user_id > b.user_id;
+ALTER TABLE exam_results DROP CONSTRAINT exam_results_pkey;
+ALTER TABLE exam_results ADD PRIMARY KEY (exam_id);
+
+DROP TABLE exam_participants;
+DELETE FROM exams WHERE group_exam;
+DROP INDEX exams_user_exam_key;
+ALTER TABLE exams ADD CONSTRAINT exams_user_id_day_exam_name_guild_id_key
+    UNIQUE (user_id, day, exam_name, guild_id);
+ALTER TABLE exams DROP COLUMN group_exam;
@@ -0,0 +1,21 @@
+-- Add up migration script here
+-- Group exams are taken by their participants instead of user_id, which is whoever created them
+ALTER TABLE exams ADD COLUMN group_exam BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- The creator isn't the owner of a group exam, so several groups can take the same exam
+ALTER TABLE exams DROP CONSTRAINT exams_user_id_day_exam_name_guild_id_key;
+CREATE UNIQUE INDEX exams_user_exam_key ON exams (user_id, day, exam_name, guild_id)
+    WHERE NOT group_exam;
+
+CREATE TABLE exam_participants (
+    exam_id INT8 NOT NULL,
+    user_id INT8 NOT NULL,
+    PRIMARY KEY (exam_id, user_id),
+    FOREIGN KEY (exam_id)
+        REFERENCES exams (exam_id)
+            ON DELETE CASCADE
+);
+
+-- Every participant of a group exam answers the follow-up separately
+ALTER TABLE exam_results DROP CONSTRAINT exam_results_pkey;
+ALTER TABLE exam_results ADD PRIMARY KEY (exam_id, user_id);
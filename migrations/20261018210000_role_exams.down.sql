@@ -0,0 +1,7 @@
+-- Add down migration script here
+DELETE FROM exams WHERE role_id IS NOT NULL;
+DROP INDEX exams_role_exam_key;
+DROP INDEX exams_user_exam_key;
+CREATE UNIQUE INDEX exams_user_exam_key ON exams (user_id, day, exam_name, guild_id)
+    WHERE NOT group_exam;
+ALTER TABLE exams DROP COLUMN role_id;
@@ -0,0 +1,10 @@
+-- Add up migration script here
+-- Role exams are taken by every member that has the role when the reminder is sent
+ALTER TABLE exams ADD COLUMN role_id INT8;
+
+-- The creator isn't the owner of a role exam either, the role is
+DROP INDEX exams_user_exam_key;
+CREATE UNIQUE INDEX exams_user_exam_key ON exams (user_id, day, exam_name, guild_id)
+    WHERE NOT group_exam AND role_id IS NULL;
+CREATE UNIQUE INDEX exams_role_exam_key ON exams (role_id, day, exam_name, guild_id)
+    WHERE role_id IS NOT NULL;
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DELETE FROM exams WHERE done;
+ALTER TABLE exams DROP COLUMN done;
+ALTER TABLE exams DROP COLUMN session_id;
+
+DROP TABLE sessions;
@@ -0,0 +1,21 @@
+-- Add up migration script here
+CREATE TABLE sessions (
+    session_id BIGSERIAL PRIMARY KEY,
+    guild_id INT8 NOT NULL,
+    name TEXT NOT NULL,
+    start_day DATE NOT NULL,
+    end_day DATE NOT NULL,
+    start_announced BOOLEAN NOT NULL DEFAULT FALSE,
+    end_announced BOOLEAN NOT NULL DEFAULT FALSE,
+    UNIQUE(guild_id, name),
+    CONSTRAINT day_check check (start_day <= end_day),
+    FOREIGN KEY (guild_id)
+        REFERENCES guilds (guild_id)
+            ON DELETE CASCADE
+);
+
+-- Exams belong to the session their day falls in
+ALTER TABLE exams ADD COLUMN session_id INT8 REFERENCES sessions (session_id) ON DELETE SET NULL;
+
+-- Exams of a session are kept after their last message, to count how many are done
+ALTER TABLE exams ADD COLUMN done BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE countdown_messages;
+
+ALTER TABLE guilds DROP COLUMN countdown_size;
@@ -0,0 +1,13 @@
+-- Add up migration script here
+-- Number of exams the live countdown lists, NULL means there's no live countdown
+ALTER TABLE guilds ADD COLUMN countdown_size INT4;
+
+-- The pinned message the scheduler keeps editing
+CREATE TABLE countdown_messages (
+    guild_id INT8 PRIMARY KEY NOT NULL,
+    channel_id INT8 NOT NULL,
+    message_id INT8 NOT NULL,
+    FOREIGN KEY (guild_id)
+        REFERENCES guilds (guild_id)
+            ON DELETE CASCADE
+);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE exams DROP COLUMN paused;
+
+ALTER TABLE guilds DROP COLUMN member_leave;
+ALTER TABLE guilds DROP COLUMN left_at;
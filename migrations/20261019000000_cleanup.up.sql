@@ -0,0 +1,9 @@
+-- Add up migration script here
+-- When the bot was removed from the guild, its data is deleted after a grace period
+ALTER TABLE guilds ADD COLUMN left_at TIMESTAMPTZ;
+
+-- What happens to the exams of members who leave the guild
+ALTER TABLE guilds ADD COLUMN member_leave TEXT NOT NULL DEFAULT 'keep';
+
+-- Exams of members who left get no messages until they come back
+ALTER TABLE exams ADD COLUMN paused BOOLEAN NOT NULL DEFAULT FALSE;
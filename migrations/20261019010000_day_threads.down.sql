@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE day_threads;
+
+ALTER TABLE guilds DROP COLUMN day_threads;
@@ -0,0 +1,15 @@
+-- Add up migration script here
+-- Reminders for the same day are sent in a thread under one message
+ALTER TABLE guilds ADD COLUMN day_threads BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE day_threads (
+    guild_id INT8 NOT NULL,
+    day DATE NOT NULL,
+    channel_id INT8 NOT NULL,
+    message_id INT8 NOT NULL,
+    thread_id INT8 NOT NULL,
+    PRIMARY KEY (guild_id, day),
+    FOREIGN KEY (guild_id)
+        REFERENCES guilds (guild_id)
+            ON DELETE CASCADE
+);
@@ -0,0 +1,106 @@
+// Runs the schedule parser outside of Discord, for trying out schedules and building test fixtures
+//
+// Usage: parse-schedule [--today YYYY-MM-DD] [--locales nl,en] [--date-order dmy|mdy] [--json] [FILE]
+// Reads the schedule from FILE, or from stdin if no file (or "-") is given.
+
+use std::{env, fs, io::Read, process};
+
+use chrono::{NaiveDate, Utc};
+use hanne_is_leuk_bot::{
+    locale,
+    schedule_parser::{self, ParseOptions},
+    Error,
+};
+
+const USAGE: &str = "Usage: parse-schedule [--today YYYY-MM-DD] [--locales nl,en] [--date-order dmy|mdy] [--json] [FILE]";
+
+struct Args {
+    options: ParseOptions,
+    json: bool,
+    file: Option<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        options: ParseOptions::new(Utc::now().date_naive()),
+        json: false,
+        file: None,
+    };
+
+    let mut iter = env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| iter.next().ok_or(format!("Missing value for {}", name));
+        match arg.as_str() {
+            "--today" => {
+                let today = value("--today")?;
+                args.options.today = NaiveDate::parse_from_str(&today, "%Y-%m-%d")
+                    .map_err(|_| format!("Invalid date for --today: {}", today))?;
+            }
+            "--locales" => {
+                args.options.locales = locale::parse_locales(&value("--locales")?)
+                    .map_err(|unknown| format!("Unknown locales: {}", unknown.join(", ")))?;
+            }
+            "--date-order" => {
+                let date_order = value("--date-order")?;
+                args.options.date_order = date_order
+                    .parse()
+                    .map_err(|_| format!("Invalid date order: {}", date_order))?;
+            }
+            "--json" => args.json = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "-" => args.file = None,
+            _ if arg.starts_with('-') => return Err(format!("Unknown option: {}", arg)),
+            _ => args.file = Some(arg),
+        }
+    }
+
+    Ok(args)
+}
+
+fn main() -> Result<(), Error> {
+    let args = parse_args().unwrap_or_else(|err| {
+        eprintln!("{}\n{}", err, USAGE);
+        process::exit(2);
+    });
+
+    let schedule = match &args.file {
+        Some(file) => fs::read_to_string(file)?,
+        None => {
+            let mut schedule = String::new();
+            std::io::stdin().read_to_string(&mut schedule)?;
+            schedule
+        }
+    };
+
+    let (exams, warnings) = schedule_parser::parse(&schedule, &args.options)?;
+
+    if args.json {
+        // Same format as the parser test fixtures
+        let output = serde_json::json!({
+            "today": args.options.today,
+            "locales": locale::format_locales(&args.options.locales),
+            "date_order": args.options.date_order.code(),
+            "exams": exams,
+            "diagnostics": warnings,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        for exam in &exams {
+            match exam.user {
+                Some(user) => println!("{} (for user {})", exam, user),
+                None => println!("{}", exam),
+            }
+        }
+        if exams.is_empty() {
+            println!("No exams found.");
+        }
+        for warning in &warnings {
+            println!("\n{}", warning);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+// Time left until someone's upcoming exams
+
+use chrono::Utc;
+use poise::{serenity_prelude::User, Context};
+
+use crate::{
+    countdown::{format_countdown, push_lines, upcoming_exams},
+    i18n::{self, tr},
+    Data, Error,
+};
+
+/// Show how long it is until each upcoming exam
+#[poise::command(
+    slash_command,
+    guild_only,
+    name_localized("nl", "aftellen"),
+    description_localized("nl", "Toon hoe lang het nog duurt tot elk komend examen")
+)]
+pub async fn countdown(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Whose exams to count down to (default: you)"]
+    #[name_localized("nl", "gebruiker")]
+    #[description_localized("nl", "Naar wiens examens afgeteld wordt (standaard: jij)")]
+    user: Option<User>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not running in a guild")?;
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+    let user = user.unwrap_or_else(|| ctx.author().clone());
+
+    let timezone = database
+        .get_guild(guild_id)
+        .await?
+        .map_or(chrono_tz::UTC, |guild| guild.message_timezone);
+    let now = Utc::now();
+    // Role exams count for members who have the role now
+    let roles = guild_id
+        .member(&ctx, user.id)
+        .await
+        .map(|member| member.roles)
+        .unwrap_or_default();
+    let exams = database
+        .get_user_countdown_exams(
+            guild_id,
+            user.id,
+            &roles,
+            now.with_timezone(&timezone).date_naive(),
+        )
+        .await?;
+    let exams = upcoming_exams(exams, timezone, now);
+    if exams.is_empty() {
+        ctx.say(tr!(language, "countdown-empty", user = user.name))
+            .await?;
+        return Ok(());
+    }
+
+    let mut message = tr!(language, "countdown-header", user = user.name);
+    let lines = exams.into_iter().map(|(exam, left)| {
+        let countdown = format_countdown(left, language);
+        if exam.exam_name.is_empty() {
+            format!("\t{}: {}", exam.day, countdown)
+        } else {
+            format!("\t{} - {}: {}", exam.day, exam.exam_name, countdown)
+        }
+    });
+    push_lines(&mut message, lines);
+    ctx.say(message).await?;
+
+    Ok(())
+}
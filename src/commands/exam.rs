@@ -3,30 +3,72 @@
 // TODO: command to delete an exam for a user
 // TODO: add a name for each exam? could serve as pkey per user (also add to db)
 
-use chrono::NaiveDate;
-use poise::Context;
+use chrono::{NaiveDate, NaiveTime};
+use poise::{
+    serenity_prelude::{Role, RoleId, User},
+    AutocompleteChoice, Context,
+};
 
-use crate::{database::DbExam, Data, Error};
+use super::optional_setting;
+use crate::{
+    database::DbExam,
+    formatter::Template,
+    i18n::{self, tr, Language},
+    Data, Error,
+};
 
+/// Add, edit and delete exams, or join group exams
+// Not limited to administrators, so members can join and leave group exams
 #[poise::command(
     slash_command,
-    required_permissions = "ADMINISTRATOR",
-    subcommands("add", "delete"),
-    guild_only
+    subcommands("add", "group", "role", "edit", "delete", "join", "leave"),
+    guild_only,
+    name_localized("nl", "examen"),
+    description_localized(
+        "nl",
+        "Examens toevoegen, aanpassen en verwijderen, of meedoen aan groepsexamens"
+    )
 )]
 pub async fn exam(_ctx: Context<'_, Data, Error>) -> Result<(), Error> {
     Ok(())
 }
 
 /// Add a new exam
-#[poise::command(slash_command, required_permissions = "ADMINISTRATOR", guild_only)]
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    guild_only,
+    name_localized("nl", "toevoegen"),
+    description_localized("nl", "Voeg een nieuw examen toe")
+)]
 pub async fn add(
     ctx: Context<'_, Data, Error>,
-    #[description = "Which user the exam is taken by"] user: serenity::model::user::User,
-    #[description = "What day the exam is. (format: \"YYYY-MM-DD\")"] day: String,
-    #[description = "The name of the exam."] name: Option<String>,
+    #[description = "Which user the exam is taken by"]
+    #[name_localized("nl", "gebruiker")]
+    #[description_localized("nl", "Welke gebruiker het examen aflegt")]
+    user: serenity::model::user::User,
+    #[description = "What day the exam is. (format: \"YYYY-MM-DD\")"]
+    #[name_localized("nl", "dag")]
+    #[description_localized("nl", "Op welke dag het examen is. (formaat: \"JJJJ-MM-DD\")")]
+    day: String,
+    #[description = "The name of the exam."]
+    #[name_localized("nl", "naam")]
+    #[description_localized("nl", "De naam van het examen.")]
+    name: Option<String>,
+    #[description = "What time the exam starts. (format: \"HH:MM\")"]
+    #[name_localized("nl", "tijd")]
+    #[description_localized("nl", "Hoe laat het examen begint. (formaat: \"UU:MM\")")]
+    time: Option<String>,
+    #[description = "Reminder for this exam only, in the same format as /settings message"]
+    #[name_localized("nl", "bericht")]
+    #[description_localized(
+        "nl",
+        "Herinnering voor alleen dit examen, in hetzelfde formaat als /instellingen bericht"
+    )]
+    message: Option<String>,
 ) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or("Not running in a guild")?;
+    let language = i18n::language(ctx).await?;
     let database = &ctx.data().database;
     let scheduler = &ctx.data().scheduler;
     let name = name.unwrap_or("".to_string()).trim().to_string();
@@ -34,38 +76,447 @@ pub async fn add(
     let day = if let Ok(day) = NaiveDate::parse_from_str(&day, "%Y-%m-%d") {
         day
     } else {
-        ctx.say(format!("Invalid date: {}", day)).await?;
+        ctx.say(tr!(language, "invalid-date", date = day)).await?;
         return Ok(());
     };
+    let time = match time.map(|time| NaiveTime::parse_from_str(&time, "%H:%M").map_err(|_| time)) {
+        Some(Ok(time)) => Some(time),
+        Some(Err(time)) => {
+            ctx.say(tr!(language, "invalid-time", time = time)).await?;
+            return Ok(());
+        }
+        None => None,
+    };
+    if let Some(Err(err)) = message.as_deref().map(Template::validate) {
+        ctx.say(tr!(language, "exam-message-invalid", error = err))
+            .await?;
+        return Ok(());
+    }
     let exam = DbExam {
         exam_id: 0,
         day,
         exam_name: name.clone(),
         guild_id,
         user_id: user.id,
+        time,
+        message,
+        reminded: false,
+        group: false,
+        role: None,
     };
 
     // Insert into db and add to scheduler
     let exam_id = database.insert_exam(exam.clone()).await?;
     scheduler.add_exam(exam_id).await?;
 
+    let user_name = user.nick_in(&ctx, guild_id).await.unwrap_or(user.name);
     if name.is_empty() {
-        ctx.say(format!(
-            "Added new exam for user {} on {}: \"{}\"",
-            user.nick_in(&ctx, guild_id)
-                .await
-                .unwrap_or_else(|| user.name),
-            day,
-            name
+        ctx.say(tr!(language, "exam-added", user = user_name, day = day))
+            .await?;
+    } else {
+        ctx.say(tr!(
+            language,
+            "exam-added-named",
+            user = user_name,
+            day = day,
+            name = name
         ))
         .await?;
+    }
+
+    Ok(())
+}
+
+async fn describe_exam(
+    ctx: Context<'_, Data, Error>,
+    language: Language,
+    exam: &DbExam,
+) -> Result<String, Error> {
+    let user_name = if let Some(role_id) = exam.role {
+        role_name(ctx, role_id)
+    } else if exam.group {
+        tr!(language, "group-exam")
     } else {
-        ctx.say(format!(
-            "Added new exam for user {} on {}",
-            user.nick_in(&ctx, guild_id)
-                .await
-                .unwrap_or_else(|| user.name),
-            day
+        exam.user_id.to_user(&ctx).await?.name
+    };
+    if !exam.exam_name.is_empty() {
+        Ok(format!("{} - {} - {}", user_name, exam.day, exam.exam_name))
+    } else {
+        Ok(format!("{} - {}", user_name, exam.day))
+    }
+}
+
+// Written out instead of mentioned, so replies don't ping the role
+pub fn role_name(ctx: Context<'_, Data, Error>, role_id: RoleId) -> String {
+    role_id
+        .to_role_cached(ctx.serenity_context())
+        .map_or_else(|| role_id.to_string(), |role| format!("@{}", role.name))
+}
+
+/// Add an exam taken by several members at once, who get one combined reminder
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    guild_only,
+    name_localized("nl", "groep"),
+    description_localized(
+        "nl",
+        "Voeg een examen toe dat door meerdere leden samen wordt afgelegd, met één herinnering"
+    )
+)]
+pub async fn group(
+    ctx: Context<'_, Data, Error>,
+    #[description = "What day the exam is. (format: \"YYYY-MM-DD\")"]
+    #[name_localized("nl", "dag")]
+    #[description_localized("nl", "Op welke dag het examen is. (formaat: \"JJJJ-MM-DD\")")]
+    day: String,
+    #[description = "The name of the exam."]
+    #[name_localized("nl", "naam")]
+    #[description_localized("nl", "De naam van het examen.")]
+    name: String,
+    #[description = "What time the exam starts. (format: \"HH:MM\")"]
+    #[name_localized("nl", "tijd")]
+    #[description_localized("nl", "Hoe laat het examen begint. (formaat: \"UU:MM\")")]
+    time: Option<String>,
+    #[description = "Reminder for this exam only, in the same format as /settings message"]
+    #[name_localized("nl", "bericht")]
+    #[description_localized(
+        "nl",
+        "Herinnering voor alleen dit examen, in hetzelfde formaat als /instellingen bericht"
+    )]
+    message: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not running in a guild")?;
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+    let scheduler = &ctx.data().scheduler;
+
+    let day = if let Ok(day) = NaiveDate::parse_from_str(&day, "%Y-%m-%d") {
+        day
+    } else {
+        ctx.say(tr!(language, "invalid-date", date = day)).await?;
+        return Ok(());
+    };
+    let time = match time.map(|time| NaiveTime::parse_from_str(&time, "%H:%M").map_err(|_| time)) {
+        Some(Ok(time)) => Some(time),
+        Some(Err(time)) => {
+            ctx.say(tr!(language, "invalid-time", time = time)).await?;
+            return Ok(());
+        }
+        None => None,
+    };
+    if let Some(Err(err)) = message.as_deref().map(Template::validate) {
+        ctx.say(tr!(language, "exam-message-invalid", error = err))
+            .await?;
+        return Ok(());
+    }
+    // The creator is stored as the user, but only participants get reminders
+    let exam = DbExam {
+        exam_id: 0,
+        day,
+        exam_name: name.trim().to_string(),
+        guild_id,
+        user_id: ctx.author().id,
+        time,
+        message,
+        reminded: false,
+        group: true,
+        role: None,
+    };
+
+    let exam_id = match database.insert_exam(exam.clone()).await {
+        Ok(exam_id) => exam_id,
+        Err(sqlx::Error::Database(err)) if err.message().contains("duplicate") => {
+            ctx.say(tr!(language, "exam-duplicate")).await?;
+            return Ok(());
+        }
+        Err(err) => return Err(err.into()),
+    };
+    scheduler.add_exam(exam_id).await?;
+
+    ctx.say(tr!(
+        language,
+        "group-created",
+        exam = describe_exam(ctx, language, &exam).await?,
+        id = exam_id
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Add an exam taken by everyone with a role when the reminder is sent
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    guild_only,
+    name_localized("nl", "rol"),
+    description_localized(
+        "nl",
+        "Voeg een examen toe voor iedereen die de rol heeft wanneer de herinnering wordt verstuurd"
+    )
+)]
+pub async fn role(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Which role takes the exam"]
+    #[name_localized("nl", "rol")]
+    #[description_localized("nl", "Welke rol het examen aflegt")]
+    role: Role,
+    #[description = "What day the exam is. (format: \"YYYY-MM-DD\")"]
+    #[name_localized("nl", "dag")]
+    #[description_localized("nl", "Op welke dag het examen is. (formaat: \"JJJJ-MM-DD\")")]
+    day: String,
+    #[description = "The name of the exam."]
+    #[name_localized("nl", "naam")]
+    #[description_localized("nl", "De naam van het examen.")]
+    name: Option<String>,
+    #[description = "What time the exam starts. (format: \"HH:MM\")"]
+    #[name_localized("nl", "tijd")]
+    #[description_localized("nl", "Hoe laat het examen begint. (formaat: \"UU:MM\")")]
+    time: Option<String>,
+    #[description = "Reminder for this exam only, in the same format as /settings message"]
+    #[name_localized("nl", "bericht")]
+    #[description_localized(
+        "nl",
+        "Herinnering voor alleen dit examen, in hetzelfde formaat als /instellingen bericht"
+    )]
+    message: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not running in a guild")?;
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+    let scheduler = &ctx.data().scheduler;
+
+    let day = if let Ok(day) = NaiveDate::parse_from_str(&day, "%Y-%m-%d") {
+        day
+    } else {
+        ctx.say(tr!(language, "invalid-date", date = day)).await?;
+        return Ok(());
+    };
+    let time = match time.map(|time| NaiveTime::parse_from_str(&time, "%H:%M").map_err(|_| time)) {
+        Some(Ok(time)) => Some(time),
+        Some(Err(time)) => {
+            ctx.say(tr!(language, "invalid-time", time = time)).await?;
+            return Ok(());
+        }
+        None => None,
+    };
+    if let Some(Err(err)) = message.as_deref().map(Template::validate) {
+        ctx.say(tr!(language, "exam-message-invalid", error = err))
+            .await?;
+        return Ok(());
+    }
+    // The creator is stored as the user, but the role's members get the reminder
+    let exam = DbExam {
+        exam_id: 0,
+        day,
+        exam_name: name.unwrap_or_default().trim().to_string(),
+        guild_id,
+        user_id: ctx.author().id,
+        time,
+        message,
+        reminded: false,
+        group: false,
+        role: Some(role.id),
+    };
+
+    let exam_id = match database.insert_exam(exam.clone()).await {
+        Ok(exam_id) => exam_id,
+        Err(sqlx::Error::Database(err)) if err.message().contains("duplicate") => {
+            ctx.say(tr!(language, "exam-duplicate")).await?;
+            return Ok(());
+        }
+        Err(err) => return Err(err.into()),
+    };
+    scheduler.add_exam(exam_id).await?;
+
+    ctx.say(tr!(
+        language,
+        "role-exam-created",
+        exam = describe_exam(ctx, language, &exam).await?,
+        id = exam_id
+    ))
+    .await?;
+
+    Ok(())
+}
+
+async fn autocomplete_group_exam(
+    ctx: Context<'_, Data, Error>,
+    partial: &str,
+) -> impl Iterator<Item = AutocompleteChoice<i64>> {
+    let exams = match ctx.guild_id() {
+        Some(guild_id) => ctx
+            .data()
+            .database
+            .get_guild_exams(guild_id)
+            .await
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+    let partial = partial.to_lowercase();
+    let mut exams: Vec<_> = exams
+        .into_iter()
+        .filter(|exam| {
+            exam.group
+                && (exam.exam_name.to_lowercase().contains(&partial)
+                    || exam.exam_id.to_string().starts_with(&partial))
+        })
+        .collect();
+    exams.sort_unstable_by_key(|exam| exam.day);
+    exams.into_iter().take(25).map(|exam| AutocompleteChoice {
+        name: format!("{} - {}", exam.day, exam.exam_name),
+        value: exam.exam_id,
+    })
+}
+
+// Loads a group exam of this guild, replying when there is none with this id
+async fn find_group_exam(
+    ctx: Context<'_, Data, Error>,
+    language: Language,
+    id: i64,
+) -> Result<Option<DbExam>, Error> {
+    let guild_id = ctx.guild_id().ok_or("Not running in a guild")?;
+    match ctx.data().database.get_exam(id).await? {
+        Some(exam) if exam.guild_id == guild_id && exam.group => Ok(Some(exam)),
+        Some(exam) if exam.guild_id == guild_id => {
+            ctx.say(tr!(language, "not-group-exam", id = id)).await?;
+            Ok(None)
+        }
+        _ => {
+            ctx.say(tr!(language, "exam-not-found", id = id)).await?;
+            Ok(None)
+        }
+    }
+}
+
+// Only administrators can add or remove someone else
+async fn participant(
+    ctx: Context<'_, Data, Error>,
+    language: Language,
+    user: Option<User>,
+) -> Result<Option<User>, Error> {
+    match user {
+        Some(user) if user.id != ctx.author().id => {
+            let member = ctx.author_member().await.ok_or("Not running in a guild")?;
+            if member.permissions(ctx)?.administrator() {
+                Ok(Some(user))
+            } else {
+                ctx.say(tr!(language, "participant-admin-only")).await?;
+                Ok(None)
+            }
+        }
+        _ => Ok(Some(ctx.author().clone())),
+    }
+}
+
+/// Join a group exam, to get its reminder
+#[poise::command(
+    slash_command,
+    guild_only,
+    ephemeral,
+    name_localized("nl", "meedoen"),
+    description_localized("nl", "Doe mee aan een groepsexamen, om de herinnering te krijgen")
+)]
+pub async fn join(
+    ctx: Context<'_, Data, Error>,
+    #[description = "The group exam"]
+    #[name_localized("nl", "examen")]
+    #[description_localized("nl", "Het groepsexamen")]
+    #[autocomplete = "autocomplete_group_exam"]
+    exam: i64,
+    #[description = "Who joins (administrators only, default: you)"]
+    #[name_localized("nl", "gebruiker")]
+    #[description_localized("nl", "Wie meedoet (alleen beheerders, standaard: jij)")]
+    user: Option<User>,
+) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
+    let user = match participant(ctx, language, user).await? {
+        Some(user) => user,
+        None => return Ok(()),
+    };
+    let exam = match find_group_exam(ctx, language, exam).await? {
+        Some(exam) => exam,
+        None => return Ok(()),
+    };
+
+    let description = describe_exam(ctx, language, &exam).await?;
+    if ctx
+        .data()
+        .database
+        .add_exam_participant(exam.exam_id, user.id)
+        .await?
+    {
+        ctx.say(tr!(
+            language,
+            "participant-joined",
+            user = user.name,
+            exam = description
+        ))
+        .await?;
+    } else {
+        ctx.say(tr!(
+            language,
+            "participant-already-joined",
+            user = user.name,
+            exam = description
+        ))
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Leave a group exam
+#[poise::command(
+    slash_command,
+    guild_only,
+    ephemeral,
+    name_localized("nl", "verlaten"),
+    description_localized("nl", "Doe niet meer mee aan een groepsexamen")
+)]
+pub async fn leave(
+    ctx: Context<'_, Data, Error>,
+    #[description = "The group exam"]
+    #[name_localized("nl", "examen")]
+    #[description_localized("nl", "Het groepsexamen")]
+    #[autocomplete = "autocomplete_group_exam"]
+    exam: i64,
+    #[description = "Who leaves (administrators only, default: you)"]
+    #[name_localized("nl", "gebruiker")]
+    #[description_localized("nl", "Wie niet meer meedoet (alleen beheerders, standaard: jij)")]
+    user: Option<User>,
+) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
+    let user = match participant(ctx, language, user).await? {
+        Some(user) => user,
+        None => return Ok(()),
+    };
+    let exam = match find_group_exam(ctx, language, exam).await? {
+        Some(exam) => exam,
+        None => return Ok(()),
+    };
+
+    let description = describe_exam(ctx, language, &exam).await?;
+    if ctx
+        .data()
+        .database
+        .remove_exam_participant(exam.exam_id, user.id)
+        .await?
+    {
+        ctx.say(tr!(
+            language,
+            "participant-left",
+            user = user.name,
+            exam = description
+        ))
+        .await?;
+    } else {
+        ctx.say(tr!(
+            language,
+            "participant-not-joined",
+            user = user.name,
+            exam = description
         ))
         .await?;
     }
@@ -73,26 +524,130 @@ pub async fn add(
     Ok(())
 }
 
+/// Change an existing exam
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    guild_only,
+    name_localized("nl", "aanpassen"),
+    description_localized("nl", "Pas een bestaand examen aan")
+)]
+pub async fn edit(
+    ctx: Context<'_, Data, Error>,
+    #[description = "ID of the exam to change"]
+    #[description_localized("nl", "ID van het examen dat je wilt aanpassen")]
+    id: i64,
+    #[description = "What day the exam is. (format: \"YYYY-MM-DD\")"]
+    #[name_localized("nl", "dag")]
+    #[description_localized("nl", "Op welke dag het examen is. (formaat: \"JJJJ-MM-DD\")")]
+    day: Option<String>,
+    #[description = "The name of the exam."]
+    #[name_localized("nl", "naam")]
+    #[description_localized("nl", "De naam van het examen.")]
+    name: Option<String>,
+    #[description = "What time the exam starts. (format: \"HH:MM\", \"none\" to clear)"]
+    #[name_localized("nl", "tijd")]
+    #[description_localized(
+        "nl",
+        "Hoe laat het examen begint. (formaat: \"UU:MM\", \"geen\" om te wissen)"
+    )]
+    time: Option<String>,
+    #[description = "Reminder for this exam only (\"none\" to use the server's messages again)"]
+    #[name_localized("nl", "bericht")]
+    #[description_localized(
+        "nl",
+        "Herinnering voor alleen dit examen (\"geen\" om weer die van de server te gebruiken)"
+    )]
+    message: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not running in a guild")?;
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+    let scheduler = &ctx.data().scheduler;
+
+    let mut exam = match database.get_exam(id).await? {
+        Some(exam) if exam.guild_id == guild_id => exam,
+        _ => {
+            ctx.say(tr!(language, "exam-not-found", id = id)).await?;
+            return Ok(());
+        }
+    };
+
+    if let Some(day) = day {
+        exam.day = if let Ok(day) = NaiveDate::parse_from_str(&day, "%Y-%m-%d") {
+            day
+        } else {
+            ctx.say(tr!(language, "invalid-date", date = day)).await?;
+            return Ok(());
+        };
+    }
+    if let Some(name) = name {
+        exam.exam_name = name.trim().to_string();
+    }
+    if let Some(time) = time {
+        exam.time = match optional_setting(time) {
+            Some(time) => match NaiveTime::parse_from_str(&time, "%H:%M") {
+                Ok(time) => Some(time),
+                Err(_) => {
+                    ctx.say(tr!(language, "invalid-time", time = time)).await?;
+                    return Ok(());
+                }
+            },
+            None => None,
+        };
+    }
+    if let Some(message) = message {
+        let message = optional_setting(message);
+        if let Some(Err(err)) = message.as_deref().map(Template::validate) {
+            ctx.say(tr!(language, "exam-message-invalid", error = err))
+                .await?;
+            return Ok(());
+        }
+        exam.message = message;
+    }
+
+    let description = describe_exam(ctx, language, &exam).await?;
+    match database.update_exam(exam.clone()).await {
+        Ok(()) => {
+            ctx.say(tr!(language, "exam-edited", exam = description))
+                .await?;
+        }
+        Err(sqlx::Error::Database(err)) if err.message().contains("duplicate") => {
+            ctx.say(tr!(language, "exam-duplicate")).await?;
+        }
+        Err(err) => return Err(err.into()),
+    }
+
+    // The day might have changed
+    scheduler.load_exams_from_database().await?;
+
+    Ok(())
+}
+
 /// Delete an existing exam
-#[poise::command(slash_command, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "verwijderen"),
+    description_localized("nl", "Verwijder een bestaand examen")
+)]
 pub async fn delete(
     ctx: Context<'_, Data, Error>,
-    #[description = "ID of the exam to delete"] id: i64,
+    #[description = "ID of the exam to delete"]
+    #[description_localized("nl", "ID van het examen dat je wilt verwijderen")]
+    id: i64,
 ) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
     let database = &ctx.data().database;
     let scheduler = &ctx.data().scheduler;
 
     if let Some(exam) = database.get_exam(id).await? {
-        let user_name = exam.user_id.to_user(&ctx).await?.name;
-        let exam_str = if !exam.exam_name.is_empty() {
-            format!("{} - {} - {}", user_name, exam.day, exam.exam_name)
-        } else {
-            format!("{} - {}", user_name, exam.day)
-        };
+        let description = describe_exam(ctx, language, &exam).await?;
         database.delete_exam(id).await?;
-        ctx.say(format!("Deleted exam {}", exam_str)).await?;
+        ctx.say(tr!(language, "exam-deleted", exam = description))
+            .await?;
     } else {
-        ctx.say(format!("No exam with id {} exists", id)).await?;
+        ctx.say(tr!(language, "exam-not-found", id = id)).await?;
     }
 
     // Reload scheduler
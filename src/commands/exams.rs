@@ -1,23 +1,31 @@
 // exam crud command
 
-use poise::{serenity_prelude::CacheHttp, Context};
+use poise::Context;
 use serenity::model::user::User;
 
-use crate::{database::DbExam, Data, Error};
+use super::exam::role_name;
+use crate::{
+    database::DbExam,
+    i18n::{self, tr, Language},
+    Data, Error,
+};
 
 /// Change the bot's settings for this server
 #[poise::command(
     slash_command,
     subcommands("guild", "user"),
     guild_only,
-    required_permissions = "ADMINISTRATOR"
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "examens"),
+    description_localized("nl", "Bekijk de examens in deze server")
 )]
 pub async fn exams(_ctx: Context<'_, Data, Error>) -> Result<(), Error> {
     Ok(())
 }
 
-async fn format_exam_list<C: CacheHttp>(
-    cache_http: C,
+async fn format_exam_list(
+    ctx: Context<'_, Data, Error>,
+    language: Language,
     exam: DbExam,
     user: bool,
     id: bool,
@@ -25,7 +33,18 @@ async fn format_exam_list<C: CacheHttp>(
     let mut message = String::with_capacity(32);
 
     if user {
-        let user_name = exam.user_id.to_user(cache_http).await?.name;
+        let user_name = if let Some(role_id) = exam.role {
+            role_name(ctx, role_id)
+        } else if exam.group {
+            let participants = ctx
+                .data()
+                .database
+                .get_exam_participants(exam.exam_id)
+                .await?;
+            tr!(language, "group-exam-count", count = participants.len())
+        } else {
+            exam.user_id.to_user(ctx).await?.name
+        };
         message.push_str(&format!("{} - ", user_name));
     }
 
@@ -43,21 +62,29 @@ async fn format_exam_list<C: CacheHttp>(
 }
 
 /// List the exams in this guild
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "server"),
+    description_localized("nl", "Toon de examens in deze server")
+)]
 pub async fn guild(ctx: Context<'_, Data, Error>) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
     let database = &ctx.data().database;
     let guild = ctx.guild().ok_or("Not running in a guild")?;
     let mut exams = database.get_guild_exams(guild.id).await?;
-    exams.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+    exams.sort_unstable_by_key(|a| a.day);
     let exams = exams;
 
     let mut message = String::with_capacity(32 + 32 * exams.len());
 
-    message.push_str(&format!("Exams in {}:\n", guild.name));
+    message.push_str(&tr!(language, "exams-guild", guild = guild.name));
+    message.push('\n');
     for exam in exams {
         message.push_str(&format!(
             "\t{}\n",
-            format_exam_list(&ctx, exam, true, true).await?
+            format_exam_list(ctx, language, exam, true, true).await?
         ));
     }
 
@@ -67,26 +94,42 @@ pub async fn guild(ctx: Context<'_, Data, Error>) -> Result<(), Error> {
 }
 
 /// List the exams for this user in this guild
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "gebruiker"),
+    description_localized("nl", "Toon de examens van deze gebruiker in deze server")
+)]
 pub async fn user(
     ctx: Context<'_, Data, Error>,
-    #[description = "User to look up the exams for"] user: User,
+    #[description = "User to look up the exams for"]
+    #[name_localized("nl", "gebruiker")]
+    #[description_localized("nl", "Gebruiker van wie je de examens wilt zien")]
+    user: User,
 ) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
     let database = &ctx.data().database;
     let guild = ctx.guild().ok_or("Not running in a guild")?;
     let mut exams = database.get_user_exams(guild.id, user.id).await?;
-    exams.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+    exams.sort_unstable_by_key(|a| a.day);
     let exams = exams;
 
     let user_name = user.name;
 
     let mut message = String::with_capacity(32 + 32 * exams.len());
 
-    message.push_str(&format!("Exams for {} in {}:\n", user_name, guild.name));
+    message.push_str(&tr!(
+        language,
+        "exams-user",
+        user = user_name,
+        guild = guild.name
+    ));
+    message.push('\n');
     for exam in exams {
         message.push_str(&format!(
             "\t{}\n",
-            format_exam_list(&ctx, exam, false, true).await?
+            format_exam_list(ctx, language, exam, false, true).await?
         ));
     }
 
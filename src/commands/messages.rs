@@ -0,0 +1,153 @@
+// Pool of reminder messages the bot picks from at random
+
+use poise::Context;
+
+use crate::{
+    database::DbMessage,
+    formatter::{MessagePool, Template},
+    i18n::{self, tr},
+    Data, Error,
+};
+
+/// Manage the random reminder messages for this server
+#[poise::command(
+    slash_command,
+    subcommands("add", "remove", "list"),
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "berichten"),
+    description_localized("nl", "Beheer de willekeurige herinneringsberichten van deze server")
+)]
+pub async fn messages(_ctx: Context<'_, Data, Error>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Add a message to the pool the bot picks reminders from
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "toevoegen"),
+    description_localized("nl", "Voeg een bericht toe waaruit de bot herinneringen kiest")
+)]
+pub async fn add(
+    ctx: Context<'_, Data, Error>,
+    #[description = "The message, in the same format as /settings message"]
+    #[name_localized("nl", "formaat")]
+    #[description_localized("nl", "Het bericht, in hetzelfde formaat als /instellingen bericht")]
+    format: String,
+    #[description = "Which exams this message is used for (default: all exams)"]
+    #[name_localized("nl", "groep")]
+    #[description_localized(
+        "nl",
+        "Voor welke examens dit bericht gebruikt wordt (standaard: alle examens)"
+    )]
+    pool: Option<MessagePool>,
+    #[description = "How much more often this message is picked than one with weight 1 (default: 1)"]
+    #[name_localized("nl", "gewicht")]
+    #[description_localized(
+        "nl",
+        "Hoeveel vaker dit bericht gekozen wordt dan een bericht met gewicht 1 (standaard: 1)"
+    )]
+    #[min = 1]
+    #[max = 100]
+    weight: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not running in a guild")?;
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+
+    if let Err(err) = Template::validate(&format) {
+        ctx.say(tr!(language, "message-invalid", error = err))
+            .await?;
+        return Ok(());
+    }
+
+    let pool = pool.unwrap_or_default();
+    let message_id = database
+        .insert_message(DbMessage {
+            message_id: 0,
+            guild_id,
+            pool,
+            format,
+            weight: weight.unwrap_or(1) as i32,
+        })
+        .await?;
+
+    ctx.say(tr!(
+        language,
+        "message-added",
+        id = message_id,
+        pool = pool.localized_name(language.code()).unwrap_or(pool.name())
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Remove a message from the pool
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "verwijderen"),
+    description_localized("nl", "Verwijder een bericht uit de berichten")
+)]
+pub async fn remove(
+    ctx: Context<'_, Data, Error>,
+    #[description = "ID of the message to remove"]
+    #[description_localized("nl", "ID van het bericht dat je wilt verwijderen")]
+    id: i64,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not running in a guild")?;
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+
+    if database.delete_message(guild_id, id).await? {
+        ctx.say(tr!(language, "message-removed", id = id)).await?;
+    } else {
+        ctx.say(tr!(language, "message-not-found", id = id)).await?;
+    }
+
+    Ok(())
+}
+
+/// List the messages in the pool
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "overzicht"),
+    description_localized("nl", "Toon de berichten waaruit de bot kiest")
+)]
+pub async fn list(ctx: Context<'_, Data, Error>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not running in a guild")?;
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+
+    let messages = database.get_guild_messages(guild_id).await?;
+    if messages.is_empty() {
+        ctx.say(tr!(language, "messages-empty")).await?;
+        return Ok(());
+    }
+
+    let mut message = tr!(language, "messages-header");
+    message.push('\n');
+    for pool_message in messages {
+        let pool = pool_message.pool;
+        message.push_str(&format!(
+            "\t{}\n",
+            tr!(
+                language,
+                "messages-entry",
+                id = pool_message.message_id,
+                format = pool_message.format,
+                pool = pool.localized_name(language.code()).unwrap_or(pool.name()),
+                weight = pool_message.weight
+            )
+        ));
+    }
+    ctx.say(message).await?;
+
+    Ok(())
+}
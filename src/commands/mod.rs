@@ -1,11 +1,26 @@
+mod countdown;
 mod exam;
 mod exams;
+mod messages;
 mod parse;
+mod results;
+mod session;
 mod settings;
 
+pub use countdown::countdown;
 pub use exam::exam;
 pub use exams::exams;
+pub use messages::messages;
 pub use parse::add_parse_menu;
 pub use parse::parse;
 pub use parse::ParseInteraction;
+pub use results::results;
+pub use session::session;
 pub use settings::settings;
+
+// "none" clears an optional setting
+fn optional_setting(value: String) -> Option<String> {
+    Some(value).filter(|value| {
+        !value.trim().eq_ignore_ascii_case("none") && !value.trim().eq_ignore_ascii_case("geen")
+    })
+}
@@ -1,80 +1,310 @@
-use std::fmt::Display;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use chrono::Utc;
 
 use poise::{
-    serenity_prelude::{GuildId, User},
+    serenity_prelude::{content_safe, Attachment, ContentSafeOptions, GuildId, User, UserId},
     Context,
 };
 
+use super::optional_setting;
 use crate::{
-    database::DbExam,
-    schedule_parser::{self, ParseExam},
+    database::{Database, DbExam},
+    formatter::Template,
+    i18n::{self, tr, Language},
+    schedule_parser::{self, ParseExam, ParseOptions, Source},
     Data, Error,
 };
 
 #[derive(Clone, Debug)]
 pub struct ParseInteraction {
-    user: User, // User that the exams are for
+    user: User, // User that the exams are for, unless the exam mentions someone
     guild_id: GuildId,
     exams: Vec<ParseExam>,
+    names: HashMap<UserId, String>, // Names of the users mentioned in the exams
+    existing: HashMap<UserId, Vec<DbExam>>, // Exams already in the bot, per user in this interaction
+    language: Language,
+}
+
+// How a parsed exam clashes with the exams that are already in the bot (or earlier in the same batch)
+enum Conflict<'e> {
+    Duplicate,
+    Rescheduled(&'e DbExam), // Same name on another day
+    SameDay(&'e str),
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+impl ParseInteraction {
+    fn user_id(&self, exam: &ParseExam) -> UserId {
+        exam.user.unwrap_or(self.user.id)
+    }
+
+    // Loads the existing exams of every user that takes one of the parsed exams
+    async fn load_existing(&mut self, database: &Database) -> Result<(), Error> {
+        let users: HashSet<_> = self.exams.iter().map(|exam| self.user_id(exam)).collect();
+        for user_id in users {
+            if !self.existing.contains_key(&user_id) {
+                let exams = database.get_user_exams(self.guild_id, user_id).await?;
+                self.existing.insert(user_id, exams);
+            }
+        }
+        Ok(())
+    }
+
+    // Turns an exam spanning several days into one exam per day, returns whether it did
+    fn expand(&mut self, index: usize) -> bool {
+        let exam = self.exams[index].clone();
+        if let Some(last_day) = exam.last_day {
+            let days = exam
+                .day
+                .iter_days()
+                .take_while(|day| *day <= last_day)
+                .map(|day| ParseExam {
+                    day,
+                    last_day: None,
+                    ..exam.clone()
+                });
+            self.exams.splice(index..index + 1, days);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn conflict(&self, index: usize) -> Option<Conflict<'_>> {
+        let exam = &self.exams[index];
+        let user_id = self.user_id(exam);
+        let existing = self.existing.get(&user_id).map_or(&[][..], |exams| exams);
+        // Earlier exams in this batch for the same user
+        let batch = self.exams[..index]
+            .iter()
+            .filter(|other| self.user_id(other) == user_id);
+
+        if existing
+            .iter()
+            .any(|other| other.day == exam.day && same_name(&other.exam_name, &exam.name))
+            || batch
+                .clone()
+                .any(|other| other.day == exam.day && same_name(&other.name, &exam.name))
+        {
+            return Some(Conflict::Duplicate);
+        }
+        if !exam.name.is_empty() {
+            if let Some(other) = existing
+                .iter()
+                .find(|other| same_name(&other.exam_name, &exam.name))
+            {
+                return Some(Conflict::Rescheduled(other));
+            }
+        }
+        existing
+            .iter()
+            .find(|other| other.day == exam.day)
+            .map(|other| other.exam_name.as_str())
+            .or_else(|| {
+                self.exams
+                    .iter()
+                    .enumerate()
+                    .find(|(i, other)| {
+                        *i != index && self.user_id(other) == user_id && other.day == exam.day
+                    })
+                    .map(|(_, other)| other.name.as_str())
+            })
+            .map(Conflict::SameDay)
+    }
 }
 
 impl Display for ParseInteraction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.exams.len() > 0 {
+        let language = self.language;
+        if !self.exams.is_empty() {
             write!(
-            f,
-            "Parsed exams for user {}\n{}\n\nuse `/parse accept` to add these exams to the bot\nuse `/parse reject` to reject these exams and stop the parsing interaction\nuse `/parse remove <id>` to remove one of these exams from the parsed exams (for example, if it was parsed incorrectly)\nuse `/parse user <user>` to change who takes these exams",
-            self.user.name,
-            self.exams
-                .iter()
-                .enumerate()
-                .map(|(i, exam)| format!("{}: {}", i + 1, exam))
-                .collect::<Vec<_>>()
-                .join("\n")
-        )
+                f,
+                "{}\n{}\n\n{}",
+                tr!(language, "parse-header", user = self.user.name),
+                self.exams
+                    .iter()
+                    .enumerate()
+                    .map(|(i, exam)| {
+                        let mut line = format!("{}: {}", i + 1, exam);
+                        match exam.user {
+                            Some(user_id) if user_id != self.user.id => {
+                                line += &tr!(
+                                    language,
+                                    "parse-for",
+                                    user = self
+                                        .names
+                                        .get(&user_id)
+                                        .cloned()
+                                        .unwrap_or_else(|| user_id.to_string())
+                                )
+                            }
+                            _ => {}
+                        }
+                        match self.conflict(i) {
+                            Some(Conflict::Duplicate) => {
+                                line += &tr!(language, "conflict-duplicate")
+                            }
+                            Some(Conflict::Rescheduled(other)) => {
+                                line += &tr!(language, "conflict-rescheduled", day = other.day)
+                            }
+                            Some(Conflict::SameDay("")) => {
+                                line += &tr!(language, "conflict-same-day")
+                            }
+                            Some(Conflict::SameDay(name)) => {
+                                line += &tr!(language, "conflict-same-day-named", name = name)
+                            }
+                            None => {}
+                        }
+                        if let Some(message) = &exam.message {
+                            line += &format!(" 💬 `{}`", message);
+                        }
+                        line
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                tr!(language, "parse-help")
+            )?;
+            if (0..self.exams.len())
+                .any(|i| matches!(self.conflict(i), Some(Conflict::Rescheduled(_))))
+            {
+                write!(f, "\n{}", tr!(language, "parse-update-hint"))?;
+            }
+            if self.exams.iter().any(|exam| exam.last_day.is_some()) {
+                write!(f, "\n{}", tr!(language, "parse-expand-hint"))?;
+            }
+            Ok(())
         } else {
-            write!(f, "No exams found in this message.")
+            write!(f, "{}", tr!(language, "parse-empty"))
         }
     }
 }
 
+// Attachments bigger than this aren't schedules
+const MAX_ATTACHMENT_SIZE: u64 = 100_000;
+
+fn is_text_attachment(attachment: &Attachment) -> bool {
+    let extension = attachment
+        .filename
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_lowercase());
+    attachment
+        .content_type
+        .as_ref()
+        .is_some_and(|content_type| content_type.starts_with("text/"))
+        || extension
+            .is_some_and(|extension| ["txt", "csv", "tsv", "md"].contains(&extension.as_str()))
+}
+
 #[poise::command(
     context_menu_command = "Parse message and add exams",
     required_permissions = "ADMINISTRATOR",
-    guild_only
+    guild_only,
+    name_localized("nl", "Examens inlezen uit bericht")
 )]
 pub async fn add_parse_menu(
     ctx: Context<'_, Data, Error>,
     msg: serenity::model::channel::Message,
 ) -> Result<(), Error> {
-    let safe_content = msg.content_safe(&ctx);
-    let (exams, warnings) = schedule_parser::parse(&safe_content)?;
+    let guild_id = ctx.guild_id().ok_or("Not running in a guild")?;
+    let language = i18n::language(ctx).await?;
+    // Downloading attachments can take a while
+    ctx.defer_ephemeral().await?;
+
+    let now = Utc::now();
+    let options = match ctx.data().database.get_guild(guild_id).await? {
+        Some(guild) => ParseOptions::for_guild(&guild, now),
+        None => ParseOptions::new(now.date_naive()),
+    };
+
+    // Keep user mentions, they decide who the exams on that line are for
+    let safe = |text: &str| {
+        content_safe(
+            ctx,
+            text,
+            &ContentSafeOptions::default().clean_user(false),
+            &msg.mentions,
+        )
+    };
+
+    // Long schedules get uploaded as text files, other bots put them in embeds
+    let mut sources = Vec::new();
+    let mut skipped = Vec::new();
+    if !msg.content.trim().is_empty() {
+        sources.push(Source {
+            name: tr!(language, "source-message"),
+            text: safe(&msg.content),
+        });
+    }
+    for attachment in msg.attachments.iter().filter(|a| is_text_attachment(a)) {
+        if attachment.size > MAX_ATTACHMENT_SIZE {
+            skipped.push(tr!(
+                language,
+                "attachment-too-large",
+                file = attachment.filename
+            ));
+            continue;
+        }
+        let text = attachment.download().await?;
+        sources.push(Source {
+            name: attachment.filename.clone(),
+            text: safe(String::from_utf8_lossy(&text).trim_start_matches('\u{feff}')),
+        });
+    }
+    for (i, embed) in msg.embeds.iter().enumerate() {
+        if let Some(description) = &embed.description {
+            sources.push(Source {
+                name: embed
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| tr!(language, "source-embed", number = i + 1)),
+                text: safe(description),
+            });
+        }
+    }
+
+    let (exams, warnings) = schedule_parser::parse_sources(&sources, &options)?;
 
     // ctx.author() is the person who invoked the command
-    let interaction = ParseInteraction {
+    let mut interaction = ParseInteraction {
         user: msg.author.clone(),
-        guild_id: ctx.guild_id().ok_or("Not running in a guild")?,
+        guild_id,
         exams: exams.clone(),
+        names: msg
+            .mentions
+            .iter()
+            .map(|user| (user.id, user.name.clone()))
+            .collect(),
+        existing: HashMap::new(),
+        language,
     };
-    if exams.len() > 0 {
+    interaction.load_existing(&ctx.data().database).await?;
+    if !exams.is_empty() {
         let mut interactions = ctx.data().parse_interactions.lock().unwrap();
         interactions.insert(ctx.author().id, interaction.clone());
     }
 
     // TODO: make sure this doesn't become too big of a message
-    ctx.defer_ephemeral().await?;
-
-    let interaction_message = format!("{}", interaction);
+    let mut interaction_message = format!("{}", interaction);
+    if !skipped.is_empty() {
+        interaction_message = format!("{}\n{}", skipped.join("\n"), interaction_message);
+    }
 
-    if warnings.len() > 0 {
+    if !warnings.is_empty() {
         let mut warnings_message = String::new();
         for warning in warnings {
-            let warning_message = warning.to_string();
+            let warning_message = warning.render_discord();
             if (interaction_message.len() + warnings_message.len() + warning_message.len() + 80)
                 > 2000
             {
-                warnings_message +=
-                    "More warnings were hidden as to not exceed the maximum message length\n";
+                warnings_message += &tr!(language, "warnings-hidden");
+                warnings_message += "\n";
                 break;
             }
             warnings_message += warning_message.as_str();
@@ -83,7 +313,7 @@ pub async fn add_parse_menu(
         ctx.say(format!("{}\n\n{}", warnings_message, interaction_message))
             .await?;
     } else {
-        ctx.say(format!("{}", interaction_message)).await?;
+        ctx.say(interaction_message).await?;
     }
 
     // msg.reply(ctx, format!("{}", interaction)).await?;
@@ -91,20 +321,39 @@ pub async fn add_parse_menu(
     Ok(())
 }
 
+/// Review the exams parsed from a message
 #[poise::command(
     slash_command,
     required_permissions = "ADMINISTRATOR",
-    subcommands("accept", "reject", "remove", "user"),
-    guild_only
+    subcommands("accept", "reject", "remove", "user", "expand", "message"),
+    guild_only,
+    name_localized("nl", "inlezen"),
+    description_localized("nl", "Controleer de examens die uit een bericht zijn ingelezen")
 )]
 pub async fn parse(_ctx: Context<'_, Data, Error>) -> Result<(), Error> {
     Ok(())
 }
 
 /// Accept the parsed exams
-#[poise::command(slash_command, required_permissions = "ADMINISTRATOR", guild_only)]
-pub async fn accept(ctx: Context<'_, Data, Error>) -> Result<(), Error> {
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    guild_only,
+    name_localized("nl", "accepteren"),
+    description_localized("nl", "Accepteer de ingelezen examens")
+)]
+pub async fn accept(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Move exams that were already added on another day, instead of adding them again"]
+    #[name_localized("nl", "verplaatsen")]
+    #[description_localized(
+        "nl",
+        "Verplaats examens die al op een andere dag zijn toegevoegd, in plaats van ze opnieuw toe te voegen"
+    )]
+    update: Option<bool>,
+) -> Result<(), Error> {
     ctx.defer_ephemeral().await?;
+    let language = i18n::language(ctx).await?;
 
     let interaction = {
         let mut interactions = ctx.data().parse_interactions.lock().unwrap();
@@ -114,19 +363,57 @@ pub async fn accept(ctx: Context<'_, Data, Error>) -> Result<(), Error> {
     let database = ctx.data().database.clone();
     let scheduler = ctx.data().scheduler.clone();
 
-    if let Some(interaction) = interaction {
+    if let Some(mut interaction) = interaction {
+        // Only exams on a single day are stored, so every day of a range gets its own exam
+        let mut expanded = 0;
+        for i in (0..interaction.exams.len()).rev() {
+            if interaction.expand(i) {
+                expanded += 1;
+            }
+        }
+
         let mut duplicates = 0;
         let mut inserted = 0;
-        for exam in interaction.exams.iter() {
-            // Insert into db and add to scheduler
+        let mut moved = HashSet::new();
+        for (i, exam) in interaction.exams.iter().enumerate() {
+            match interaction.conflict(i) {
+                Some(Conflict::Duplicate) => {
+                    duplicates += 1;
+                    continue;
+                }
+                Some(Conflict::Rescheduled(other))
+                    if update.unwrap_or(false) && !moved.contains(&other.exam_id) =>
+                {
+                    match database.update_exam_day(other.exam_id, exam.day).await {
+                        Ok(()) => {
+                            moved.insert(other.exam_id);
+                        }
+                        Err(sqlx::Error::Database(err)) if err.message().contains("duplicate") => {
+                            // The exam was already added on its new day since the schedule was parsed
+                            duplicates += 1;
+                        }
+                        err => {
+                            err?;
+                        }
+                    }
+                    continue;
+                }
+                _ => {}
+            }
 
+            // Insert into db and add to scheduler
             match database
                 .insert_exam(DbExam {
                     day: exam.day,
                     exam_id: 0,
                     exam_name: exam.name.to_owned(),
                     guild_id: interaction.guild_id,
-                    user_id: interaction.user.id,
+                    user_id: interaction.user_id(exam),
+                    time: exam.time.map(|time| time.start),
+                    message: exam.message.clone(),
+                    reminded: false,
+                    group: false,
+                    role: None,
                 })
                 .await
             {
@@ -143,48 +430,71 @@ pub async fn accept(ctx: Context<'_, Data, Error>) -> Result<(), Error> {
                 }
             }
         }
-        let message = if duplicates > 0 {
-            format!(
-                "Inserted {} exams. {} duplicates already in the bot.",
-                inserted, duplicates
-            )
-        } else {
-            format!("Inserted {} exams.", inserted)
-        };
+        if !moved.is_empty() {
+            // The moved exams are still scheduled on their old day
+            scheduler.load_exams_from_database().await?;
+        }
+
+        let mut message = tr!(language, "parse-inserted", count = inserted);
+        if expanded > 0 {
+            message += &tr!(language, "parse-expanded", count = expanded);
+        }
+        if !moved.is_empty() {
+            message += &tr!(language, "parse-moved", count = moved.len());
+        }
+        if duplicates > 0 {
+            message += &tr!(language, "parse-duplicates", count = duplicates);
+        }
         ctx.say(message).await?;
     } else {
-        ctx.say("You don't have an ongoing 'parse' interaction. Use the context menu to start one first! (right click > Apps > Parse message and add exams)").await?;
+        ctx.say(tr!(language, "parse-no-interaction")).await?;
     }
 
     Ok(())
 }
 
 /// Reject the parsed exams
-#[poise::command(slash_command, required_permissions = "ADMINISTRATOR", guild_only)]
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    guild_only,
+    name_localized("nl", "afwijzen"),
+    description_localized("nl", "Wijs de ingelezen examens af")
+)]
 pub async fn reject(ctx: Context<'_, Data, Error>) -> Result<(), Error> {
     ctx.defer_ephemeral().await?;
+    let language = i18n::language(ctx).await?;
 
     let interaction = {
         let mut interactions = ctx.data().parse_interactions.lock().unwrap();
         interactions.remove(&ctx.author().id)
     };
 
-    if let Some(_) = interaction {
-        ctx.say("Rejected parse interaction.").await?;
+    if interaction.is_some() {
+        ctx.say(tr!(language, "parse-rejected")).await?;
     } else {
-        ctx.say("You don't have an ongoing 'parse' interaction. Use the context menu to start one first! (right click > Apps > Parse message and add exams)").await?;
+        ctx.say(tr!(language, "parse-no-interaction")).await?;
     }
 
     Ok(())
 }
 
 /// Remove one of the parsed exams
-#[poise::command(slash_command, required_permissions = "ADMINISTRATOR", guild_only)]
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    guild_only,
+    name_localized("nl", "verwijderen"),
+    description_localized("nl", "Verwijder een van de ingelezen examens")
+)]
 pub async fn remove(
     ctx: Context<'_, Data, Error>,
-    #[description = "ID of the exam to remove"] id: usize,
+    #[description = "ID of the exam to remove"]
+    #[description_localized("nl", "ID van het examen dat je wilt verwijderen")]
+    id: usize,
 ) -> Result<(), Error> {
     ctx.defer_ephemeral().await?;
+    let language = i18n::language(ctx).await?;
 
     let message = {
         let mut interactions = ctx.data().parse_interactions.lock().unwrap();
@@ -196,10 +506,10 @@ pub async fn remove(
                 interaction.exams.remove(id - 1);
                 format!("{}", interaction)
             } else {
-                "Invalid index".to_owned()
+                tr!(language, "invalid-index")
             }
         } else {
-            "You don't have an ongoing 'parse' interaction. Use the context menu to start one first! (right click > Apps > Parse message and add exams)".to_owned()
+            tr!(language, "parse-no-interaction")
         }
     };
 
@@ -208,23 +518,104 @@ pub async fn remove(
     Ok(())
 }
 
-/// Change the user this exam is parsed for
-#[poise::command(slash_command, required_permissions = "ADMINISTRATOR", guild_only)]
+/// Change the user these exams are parsed for
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    guild_only,
+    name_localized("nl", "gebruiker"),
+    description_localized("nl", "Verander voor wie deze examens zijn")
+)]
 pub async fn user(
     ctx: Context<'_, Data, Error>,
-    #[description = "User to change to"] user: User,
+    #[description = "User to change to"]
+    #[name_localized("nl", "gebruiker")]
+    #[description_localized("nl", "De nieuwe gebruiker")]
+    user: User,
+    #[description = "ID of the exam to change, leave empty to change all exams"]
+    #[description_localized(
+        "nl",
+        "ID van het examen dat je wilt veranderen, laat leeg om alle examens te veranderen"
+    )]
+    id: Option<usize>,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let language = i18n::language(ctx).await?;
+
+    // Needed to check the exams for conflicts once they belong to this user
+    let guild_id = ctx.guild_id().ok_or("Not running in a guild")?;
+    let user_exams = ctx
+        .data()
+        .database
+        .get_user_exams(guild_id, user.id)
+        .await?;
+
+    let message = {
+        let mut interactions = ctx.data().parse_interactions.lock().unwrap();
+        let interaction = interactions.get_mut(&ctx.author().id);
+
+        if let Some(interaction) = interaction {
+            interaction.existing.insert(user.id, user_exams);
+            let num_exams = interaction.exams.len();
+            match id {
+                Some(id) if (id >= 1) && (id <= num_exams) => {
+                    interaction.exams[id - 1].user = Some(user.id);
+                    interaction.names.insert(user.id, user.name);
+                    format!("{}", interaction)
+                }
+                Some(_) => tr!(language, "invalid-index"),
+                None => {
+                    for exam in interaction.exams.iter_mut() {
+                        exam.user = None;
+                    }
+                    interaction.user = user;
+                    format!("{}", interaction)
+                }
+            }
+        } else {
+            tr!(language, "parse-no-interaction")
+        }
+    };
+
+    ctx.say(message).await?;
+
+    Ok(())
+}
+
+/// Split an exam spanning several days into one exam per day
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    guild_only,
+    name_localized("nl", "uitsplitsen"),
+    description_localized("nl", "Splits een examen over meerdere dagen op in één examen per dag")
+)]
+pub async fn expand(
+    ctx: Context<'_, Data, Error>,
+    #[description = "ID of the exam to expand"]
+    #[description_localized("nl", "ID van het examen dat je wilt uitsplitsen")]
+    id: usize,
 ) -> Result<(), Error> {
     ctx.defer_ephemeral().await?;
+    let language = i18n::language(ctx).await?;
 
     let message = {
         let mut interactions = ctx.data().parse_interactions.lock().unwrap();
         let interaction = interactions.get_mut(&ctx.author().id);
 
         if let Some(interaction) = interaction {
-            interaction.user = user;
-            format!("{}", interaction)
+            let num_exams = interaction.exams.len();
+            if (id >= 1) && (id <= num_exams) {
+                if interaction.expand(id - 1) {
+                    format!("{}", interaction)
+                } else {
+                    tr!(language, "parse-single-day")
+                }
+            } else {
+                tr!(language, "invalid-index")
+            }
         } else {
-            "You don't have an ongoing 'parse' interaction. Use the context menu to start one first! (right click > Apps > Parse message and add exams)".to_owned()
+            tr!(language, "parse-no-interaction")
         }
     };
 
@@ -232,3 +623,56 @@ pub async fn user(
 
     Ok(())
 }
+
+/// Give one of the parsed exams its own reminder message
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    guild_only,
+    name_localized("nl", "bericht"),
+    description_localized("nl", "Geef een van de ingelezen examens een eigen herinnering")
+)]
+pub async fn message(
+    ctx: Context<'_, Data, Error>,
+    #[description = "ID of the exam to change"]
+    #[description_localized("nl", "ID van het examen dat je wilt aanpassen")]
+    id: usize,
+    #[description = "The message, in the same format as /settings message (\"none\" to remove it)"]
+    #[name_localized("nl", "bericht")]
+    #[description_localized(
+        "nl",
+        "Het bericht, in hetzelfde formaat als /instellingen bericht (\"geen\" om het te wissen)"
+    )]
+    message: String,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let language = i18n::language(ctx).await?;
+
+    let message = optional_setting(message);
+    if let Some(Err(err)) = message.as_deref().map(Template::validate) {
+        ctx.say(tr!(language, "exam-message-invalid", error = err))
+            .await?;
+        return Ok(());
+    }
+
+    let reply = {
+        let mut interactions = ctx.data().parse_interactions.lock().unwrap();
+        let interaction = interactions.get_mut(&ctx.author().id);
+
+        if let Some(interaction) = interaction {
+            let num_exams = interaction.exams.len();
+            if (id >= 1) && (id <= num_exams) {
+                interaction.exams[id - 1].message = message;
+                format!("{}", interaction)
+            } else {
+                tr!(language, "invalid-index")
+            }
+        } else {
+            tr!(language, "parse-no-interaction")
+        }
+    };
+
+    ctx.say(reply).await?;
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+// Answers to the follow-up messages after exams
+
+use poise::Context;
+
+use crate::{
+    i18n::{self, tr},
+    Data, Error,
+};
+
+/// Show how your exams went, according to your answers to the follow-up messages
+#[poise::command(
+    slash_command,
+    guild_only,
+    ephemeral,
+    name_localized("nl", "resultaten"),
+    description_localized(
+        "nl",
+        "Bekijk hoe je examens gingen, volgens je antwoorden op de vervolgberichten"
+    )
+)]
+pub async fn results(ctx: Context<'_, Data, Error>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not running in a guild")?;
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+
+    let results = database.get_user_results(guild_id, ctx.author().id).await?;
+    if results.is_empty() {
+        ctx.say(tr!(language, "results-empty")).await?;
+        return Ok(());
+    }
+
+    let mut message = tr!(language, "results-header");
+    message.push('\n');
+    for result in results {
+        let answer = result
+            .result
+            .map_or(tr!(language, "results-no-answer"), |result| {
+                result.emoji().to_owned()
+            });
+        if result.exam_name.is_empty() {
+            message.push_str(&format!("\t{}: {}\n", result.day, answer));
+        } else {
+            message.push_str(&format!(
+                "\t{} - {}: {}\n",
+                result.day, result.exam_name, answer
+            ));
+        }
+    }
+    ctx.say(message).await?;
+
+    Ok(())
+}
@@ -0,0 +1,323 @@
+// Exam sessions, the periods exams are grouped in
+
+use chrono::{NaiveDate, Utc};
+use poise::{serenity_prelude::User, Context};
+
+use crate::{
+    database::DbSession,
+    i18n::{self, tr, Language},
+    Data, Error,
+};
+
+/// Exam sessions of this server, like the January exams
+// Not limited to administrators, so members can see their progress
+#[poise::command(
+    slash_command,
+    subcommands("add", "delete", "list", "progress"),
+    guild_only,
+    name_localized("nl", "sessie"),
+    description_localized("nl", "Examensessies van deze server, zoals de januarizit")
+)]
+pub async fn session(_ctx: Context<'_, Data, Error>) -> Result<(), Error> {
+    Ok(())
+}
+
+async fn autocomplete_session(
+    ctx: Context<'_, Data, Error>,
+    partial: &str,
+) -> impl Iterator<Item = String> {
+    let sessions = match ctx.guild_id() {
+        Some(guild_id) => ctx
+            .data()
+            .database
+            .get_guild_sessions(guild_id)
+            .await
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+    let partial = partial.to_lowercase();
+    sessions
+        .into_iter()
+        .map(|session| session.name)
+        .filter(move |name| name.to_lowercase().contains(&partial))
+        .take(25)
+}
+
+// Finds a session of this guild by name, replying when there is none
+async fn find_session(
+    ctx: Context<'_, Data, Error>,
+    language: Language,
+    name: &str,
+) -> Result<Option<DbSession>, Error> {
+    let guild_id = ctx.guild_id().ok_or("Not running in a guild")?;
+    let session = ctx
+        .data()
+        .database
+        .get_guild_sessions(guild_id)
+        .await?
+        .into_iter()
+        .find(|session| session.name.eq_ignore_ascii_case(name.trim()));
+    if session.is_none() {
+        ctx.say(tr!(language, "session-not-found", session = name))
+            .await?;
+    }
+    Ok(session)
+}
+
+/// Add an exam session, exams on its days belong to it
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    guild_only,
+    name_localized("nl", "toevoegen"),
+    description_localized("nl", "Voeg een examensessie toe, examens op haar dagen horen erbij")
+)]
+pub async fn add(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Name of the session"]
+    #[name_localized("nl", "naam")]
+    #[description_localized("nl", "Naam van de sessie")]
+    name: String,
+    #[description = "First day of the session. (format: \"YYYY-MM-DD\")"]
+    #[name_localized("nl", "begin")]
+    #[description_localized("nl", "Eerste dag van de sessie. (formaat: \"JJJJ-MM-DD\")")]
+    start: String,
+    #[description = "Last day of the session. (format: \"YYYY-MM-DD\")"]
+    #[name_localized("nl", "einde")]
+    #[description_localized("nl", "Laatste dag van de sessie. (formaat: \"JJJJ-MM-DD\")")]
+    end: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not running in a guild")?;
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+
+    let mut days = Vec::with_capacity(2);
+    for day in [start, end] {
+        match NaiveDate::parse_from_str(&day, "%Y-%m-%d") {
+            Ok(day) => days.push(day),
+            Err(_) => {
+                ctx.say(tr!(language, "invalid-date", date = day)).await?;
+                return Ok(());
+            }
+        }
+    }
+    let (start_day, end_day) = (days[0], days[1]);
+    if start_day > end_day {
+        ctx.say(tr!(language, "session-invalid-range")).await?;
+        return Ok(());
+    }
+
+    // Every day belongs to at most one session
+    let sessions = database.get_guild_sessions(guild_id).await?;
+    if let Some(other) = sessions
+        .iter()
+        .find(|other| other.start_day <= end_day && start_day <= other.end_day)
+    {
+        ctx.say(tr!(language, "session-overlap", session = other.name))
+            .await?;
+        return Ok(());
+    }
+
+    let name = name.trim().to_string();
+    let session_id = match database
+        .insert_session(DbSession {
+            session_id: 0,
+            guild_id,
+            name: name.clone(),
+            start_day,
+            end_day,
+            start_announced: false,
+            end_announced: false,
+        })
+        .await
+    {
+        Ok(session_id) => session_id,
+        Err(sqlx::Error::Database(err)) if err.message().contains("duplicate") => {
+            ctx.say(tr!(language, "session-duplicate", session = name))
+                .await?;
+            return Ok(());
+        }
+        Err(err) => return Err(err.into()),
+    };
+    ctx.data().scheduler.load_sessions_from_database().await?;
+
+    let (_, total) = database.count_session_exams(session_id).await?;
+    ctx.say(tr!(
+        language,
+        "session-added",
+        session = name,
+        start = start_day,
+        end = end_day,
+        count = total
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Delete an exam session, its exams are kept
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    guild_only,
+    name_localized("nl", "verwijderen"),
+    description_localized("nl", "Verwijder een examensessie, haar examens blijven bestaan")
+)]
+pub async fn delete(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Name of the session"]
+    #[name_localized("nl", "naam")]
+    #[description_localized("nl", "Naam van de sessie")]
+    #[autocomplete = "autocomplete_session"]
+    name: String,
+) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
+
+    if let Some(session) = find_session(ctx, language, &name).await? {
+        ctx.data()
+            .database
+            .delete_session(session.session_id)
+            .await?;
+        ctx.data().scheduler.load_sessions_from_database().await?;
+        ctx.say(tr!(language, "session-deleted", session = session.name))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// List the exam sessions of this server
+#[poise::command(
+    slash_command,
+    guild_only,
+    name_localized("nl", "lijst"),
+    description_localized("nl", "Toon de examensessies van deze server")
+)]
+pub async fn list(ctx: Context<'_, Data, Error>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not running in a guild")?;
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+
+    let sessions = database.get_guild_sessions(guild_id).await?;
+    if sessions.is_empty() {
+        ctx.say(tr!(language, "session-list-empty")).await?;
+        return Ok(());
+    }
+
+    let mut message = tr!(language, "session-list-header");
+    message.push('\n');
+    for session in sessions {
+        let (done, total) = database.count_session_exams(session.session_id).await?;
+        message.push_str(&format!(
+            "\t{}\n",
+            tr!(
+                language,
+                "session-list-entry",
+                session = session.name,
+                start = session.start_day,
+                end = session.end_day,
+                done = done,
+                count = total
+            )
+        ));
+    }
+    ctx.say(message).await?;
+
+    Ok(())
+}
+
+/// Show how many exams of a session are done
+#[poise::command(
+    slash_command,
+    guild_only,
+    ephemeral,
+    name_localized("nl", "voortgang"),
+    description_localized("nl", "Toon hoeveel examens van een sessie al achter de rug zijn")
+)]
+pub async fn progress(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Name of the session (default: the current or next one)"]
+    #[name_localized("nl", "naam")]
+    #[description_localized("nl", "Naam van de sessie (standaard: de huidige of volgende)")]
+    #[autocomplete = "autocomplete_session"]
+    name: Option<String>,
+    #[description = "Whose progress to show (default: you)"]
+    #[name_localized("nl", "gebruiker")]
+    #[description_localized("nl", "Van wie de voortgang getoond wordt (standaard: jij)")]
+    user: Option<User>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not running in a guild")?;
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+    let user = user.unwrap_or_else(|| ctx.author().clone());
+
+    let session = match name {
+        Some(name) => match find_session(ctx, language, &name).await? {
+            Some(session) => session,
+            None => return Ok(()),
+        },
+        None => {
+            let today = match database.get_guild(guild_id).await? {
+                Some(guild) => Utc::now()
+                    .with_timezone(&guild.message_timezone)
+                    .date_naive(),
+                None => Utc::now().date_naive(),
+            };
+            let sessions = database.get_guild_sessions(guild_id).await?;
+            let current = sessions
+                .iter()
+                .find(|session| session.end_day >= today)
+                .or(sessions.last())
+                .cloned();
+            match current {
+                Some(session) => session,
+                None => {
+                    ctx.say(tr!(language, "session-list-empty")).await?;
+                    return Ok(());
+                }
+            }
+        }
+    };
+
+    // Role exams count for members who have the role now
+    let roles = guild_id
+        .member(&ctx, user.id)
+        .await
+        .map(|member| member.roles)
+        .unwrap_or_default();
+    let exams = database
+        .get_user_session_exams(session.session_id, user.id, &roles)
+        .await?;
+    if exams.is_empty() {
+        ctx.say(tr!(
+            language,
+            "session-progress-empty",
+            user = user.name,
+            session = session.name
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let done = exams.iter().filter(|exam| exam.done).count();
+    let mut message = tr!(
+        language,
+        "session-progress",
+        user = user.name,
+        done = done,
+        count = exams.len(),
+        session = session.name
+    );
+    message.push('\n');
+    for exam in exams {
+        let mark = if exam.done { "✅" } else { "⬜" };
+        if exam.exam_name.is_empty() {
+            message.push_str(&format!("\t{} {}\n", mark, exam.day));
+        } else {
+            message.push_str(&format!("\t{} {} - {}\n", mark, exam.day, exam.exam_name));
+        }
+    }
+    ctx.say(message).await?;
+
+    Ok(())
+}
@@ -1,35 +1,63 @@
-use chrono::{NaiveTime, Utc};
+use chrono::{Days, NaiveTime, Utc};
 use chrono_tz::Tz;
 use poise::{serenity_prelude::Mentionable, Context};
 use serenity::model::channel::Channel;
 
+use super::optional_setting;
 use crate::{
-    database::{DbExam, DbGuild},
+    database::{DbExam, DbGuild, MemberLeave},
     default_channel,
-    formatter::{format_exam, DEFAULT_FORMAT},
+    formatter::{default_format, Delivery, Template, Variables},
+    i18n::{self, tr, Language},
+    locale::{self, LOCALES},
+    schedule_parser::DateOrder,
     Data, Error,
 };
 
 /// Change the bot's settings for this server
 #[poise::command(
     slash_command,
-    subcommands("channel", "time", "list", "message"),
+    subcommands(
+        "channel",
+        "time",
+        "list",
+        "message",
+        "locales",
+        "date_order",
+        "delivery",
+        "embed",
+        "language",
+        "followup",
+        "countdown",
+        "member_leave",
+        "threads"
+    ),
     guild_only,
-    required_permissions = "ADMINISTRATOR"
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "instellingen"),
+    description_localized("nl", "Verander de instellingen van de bot voor deze server")
 )]
 pub async fn settings(_ctx: Context<'_, Data, Error>) -> Result<(), Error> {
     Ok(())
 }
 
 /// Change the channel in which this bot sends messages
-#[poise::command(slash_command, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "kanaal"),
+    description_localized("nl", "Verander het kanaal waarin de bot berichten stuurt")
+)]
 pub async fn channel(
     ctx: Context<'_, Data, Error>,
     #[description = "What channel to send messages in"]
+    #[name_localized("nl", "kanaal")]
+    #[description_localized("nl", "In welk kanaal de bot berichten stuurt")]
     #[channel_types("Text")]
     channel: Channel,
 ) -> Result<(), Error> {
     // Get current settings
+    let language = i18n::language(ctx).await?;
     let database = &ctx.data().database;
 
     let guild = ctx.guild().ok_or("Not running in a guild")?;
@@ -37,13 +65,10 @@ pub async fn channel(
 
     let current_user_id = ctx.serenity_context().cache.current_user_id();
 
-    let permissions = channel.permissions_for_user(&ctx, &current_user_id)?;
+    let permissions = channel.permissions_for_user(ctx, current_user_id)?;
     if !permissions.send_messages() {
-        ctx.say(format!(
-            "This bot doesn't have permissions to send messages in {}",
-            channel
-        ))
-        .await?;
+        ctx.say(tr!(language, "no-send-permission", channel = channel))
+            .await?;
         return Ok(());
     }
 
@@ -53,43 +78,45 @@ pub async fn channel(
         guild_settings
     } else {
         // Insert (shouldn't happen but ok)
-        DbGuild {
-            guild_id: guild.id,
-            message_channel_id: default_channel(ctx.serenity_context(), &guild).await?,
-            message_time: NaiveTime::from_hms_opt(21, 0, 0).unwrap(),
-            message_timezone: chrono_tz::UTC,
-            format: DEFAULT_FORMAT.to_string(),
-        }
+        DbGuild::new(guild.id, channel.id)
     };
 
     database.set_guild(guild_settings).await?;
 
-    ctx.say(format!("Updated bot message channel to {}!", channel))
+    ctx.say(tr!(language, "channel-updated", channel = channel))
         .await?;
     Ok(())
 }
 
 /// Change the time at which this bot sends messages
-#[poise::command(slash_command, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "tijd"),
+    description_localized("nl", "Verander hoe laat de bot berichten stuurt")
+)]
 pub async fn time(
     ctx: Context<'_, Data, Error>,
     #[description = "What channel to send messages in (24h notation, format: \"hour:minute\")"]
+    #[name_localized("nl", "tijd")]
+    #[description_localized(
+        "nl",
+        "Hoe laat de bot berichten stuurt (24-uursnotatie, formaat: \"uur:minuut\")"
+    )]
     time: String,
-    #[description = "What timezone to use."] timezone: String,
+    #[description = "What timezone to use."]
+    #[name_localized("nl", "tijdzone")]
+    #[description_localized("nl", "Welke tijdzone je wilt gebruiken.")]
+    timezone: String,
 ) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
     let database = &ctx.data().database;
 
     let guild = ctx.guild().ok_or("Not running in a guild")?;
 
-    let timezone: Option<Tz> = match timezone.parse() {
-        Ok(timezone) => Some(timezone),
-        Err(_) => None,
-    };
+    let timezone: Option<Tz> = timezone.parse().ok();
 
-    let time: Option<chrono::NaiveTime> = match chrono::NaiveTime::parse_from_str(&time, "%H:%M") {
-        Ok(time) => Some(time),
-        Err(_) => None,
-    };
+    let time: Option<chrono::NaiveTime> = chrono::NaiveTime::parse_from_str(&time, "%H:%M").ok();
 
     match (time, timezone) {
         (Some(time), Some(timezone)) => {
@@ -101,29 +128,32 @@ pub async fn time(
                     guild_settings
                 } else {
                     // Insert (shouldn't happen but ok)
-                    DbGuild {
-                        guild_id: guild.id,
-                        message_channel_id: default_channel(ctx.serenity_context(), &guild).await?,
-                        message_time: NaiveTime::from_hms_opt(21, 0, 0).unwrap(),
-                        message_timezone: chrono_tz::UTC,
-                        format: DEFAULT_FORMAT.to_string(),
-                    }
+                    let mut guild_settings = DbGuild::new(
+                        guild.id,
+                        default_channel(ctx.serenity_context(), &guild).await?,
+                    );
+                    guild_settings.message_time = time;
+                    guild_settings.message_timezone = timezone;
+                    guild_settings
                 };
             database.set_guild(guild_settings).await?;
-            ctx.say(format!(
-                "Updated bot message time to {} {}!",
-                time, timezone
+            ctx.say(tr!(
+                language,
+                "time-updated",
+                time = time,
+                timezone = timezone
             ))
             .await?;
         }
         (Some(_), None) => {
-            ctx.say("Invalid timezone").await?;
+            ctx.say(tr!(language, "invalid-timezone")).await?;
         }
         (None, Some(_)) => {
-            ctx.say("Invalid time format").await?;
+            ctx.say(tr!(language, "invalid-time-format")).await?;
         }
         (None, None) => {
-            ctx.say("Invalid time format and timezone").await?;
+            ctx.say(tr!(language, "invalid-time-format-timezone"))
+                .await?;
         }
     }
 
@@ -134,72 +164,720 @@ pub async fn time(
 }
 
 /// Change the message this bot sends
-#[poise::command(slash_command, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "bericht"),
+    description_localized("nl", "Verander het bericht dat de bot stuurt")
+)]
 pub async fn message(
     ctx: Context<'_, Data, Error>,
-    #[description = "The format string the bot uses."] format: String,
+    #[description = "The message, with variables like {name} and {if name}...{end} blocks (\"none\" for the default)"]
+    #[name_localized("nl", "formaat")]
+    #[description_localized(
+        "nl",
+        "Het bericht, met variabelen zoals {name} en {if name}...{else}...{end} (\"geen\" voor de standaard)"
+    )]
+    format: String,
 ) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
     let database = &ctx.data().database;
 
     let guild = ctx.guild().ok_or("Not running in a guild")?;
 
-    let guild_settings = if let Some(mut guild_settings) = database.get_guild(guild.id).await? {
-        // Modify
-        guild_settings.format = format.clone();
+    let mut guild_settings = if let Some(guild_settings) = database.get_guild(guild.id).await? {
         guild_settings
     } else {
         // Insert (shouldn't happen but ok)
-        DbGuild {
-            guild_id: guild.id,
-            message_channel_id: default_channel(ctx.serenity_context(), &guild).await?,
-            message_time: NaiveTime::from_hms_opt(21, 0, 0).unwrap(),
-            message_timezone: chrono_tz::UTC,
-            format: format.clone(),
+        DbGuild::new(
+            guild.id,
+            default_channel(ctx.serenity_context(), &guild).await?,
+        )
+    };
+
+    // An invalid format would be sent to everyone, so it's never saved
+    let format = optional_setting(format);
+    let template = match Template::validate(format.as_deref().unwrap_or(default_format(language))) {
+        Ok(template) => template,
+        Err(err) => {
+            ctx.say(tr!(
+                language,
+                "format-invalid",
+                error = err,
+                format = guild_settings
+                    .format
+                    .as_deref()
+                    .unwrap_or(default_format(language))
+            ))
+            .await?;
+            return Ok(());
         }
     };
+    guild_settings.format = format.clone();
 
+    // Reminders are sent the day before the exam
+    let today = Utc::now()
+        .with_timezone(&guild_settings.message_timezone)
+        .date_naive();
     let nameless_exam = DbExam {
-        day: Utc::now().date_naive(),
+        day: today + Days::new(1),
         exam_id: 0,
         exam_name: "".to_string(),
         guild_id: guild.id,
         user_id: ctx.author().id,
+        time: None,
+        message: None,
+        reminded: false,
+        group: false,
+        role: None,
     };
     let named_exam = DbExam {
-        day: Utc::now().date_naive(),
+        day: today + Days::new(1),
         exam_id: 0,
-        exam_name: "Algorithms and Datastructures".to_string(),
+        exam_name: tr!(language, "example-exam-name"),
         guild_id: guild.id,
         user_id: ctx.author().id,
+        time: NaiveTime::from_hms_opt(9, 0, 0),
+        message: None,
+        reminded: false,
+        group: false,
+        role: None,
     };
+    let nameless_example = template.render(&Variables::new(
+        &nameless_exam,
+        today,
+        &guild.name,
+        language,
+    ));
+    let named_example = template.render(&Variables::new(&named_exam, today, &guild.name, language));
 
     database.set_guild(guild_settings).await?;
-    let message = format!("Updated bot format to \"{}\"!\n\nExample for nameless exam:\n{}\n\nExample for named exam:\n{}", format, format_exam(&format, nameless_exam), format_exam(&format, named_exam));
+    let message = tr!(
+        language,
+        "format-updated",
+        format = format.as_deref().unwrap_or(default_format(language)),
+        nameless = nameless_example,
+        named = named_example
+    );
     ctx.say(message).await?;
 
     Ok(())
 }
 
+/// Change the languages month and weekday names are recognised in when parsing schedules
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "inleestalen"),
+    description_localized(
+        "nl",
+        "Verander in welke talen maand- en dagnamen herkend worden bij het inlezen van roosters"
+    )
+)]
+pub async fn locales(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Comma separated list of language codes (for example \"nl,en\")"]
+    #[name_localized("nl", "talen")]
+    #[description_localized(
+        "nl",
+        "Lijst van taalcodes gescheiden door komma's (bijvoorbeeld \"nl,en\")"
+    )]
+    locales: String,
+) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+
+    let guild = ctx.guild().ok_or("Not running in a guild")?;
+
+    let locales = match locale::parse_locales(&locales) {
+        Ok(locales) if !locales.is_empty() => locales,
+        Ok(_) => {
+            ctx.say(tr!(language, "locales-empty")).await?;
+            return Ok(());
+        }
+        Err(unknown) => {
+            ctx.say(tr!(
+                language,
+                "locales-unknown",
+                unknown = unknown.join(", "),
+                available = LOCALES
+                    .iter()
+                    .map(|locale| format!("{} ({})", locale.code, locale.name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let guild_settings = if let Some(mut guild_settings) = database.get_guild(guild.id).await? {
+        // Modify
+        guild_settings.parse_locales = locales.clone();
+        guild_settings
+    } else {
+        // Insert (shouldn't happen but ok)
+        let mut guild_settings = DbGuild::new(
+            guild.id,
+            default_channel(ctx.serenity_context(), &guild).await?,
+        );
+        guild_settings.parse_locales = locales.clone();
+        guild_settings
+    };
+
+    database.set_guild(guild_settings).await?;
+
+    ctx.say(tr!(
+        language,
+        "locales-updated",
+        locales = locales
+            .iter()
+            .map(|locale| locale.name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Change how numeric dates like 3/4 are read when parsing schedules
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "datumvolgorde"),
+    description_localized(
+        "nl",
+        "Verander hoe numerieke datums zoals 3/4 gelezen worden bij het inlezen van roosters"
+    )
+)]
+pub async fn date_order(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Whether the day or the month comes first"]
+    #[name_localized("nl", "volgorde")]
+    #[description_localized("nl", "Of de dag of de maand eerst komt")]
+    order: DateOrder,
+) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+
+    let guild = ctx.guild().ok_or("Not running in a guild")?;
+
+    let guild_settings = if let Some(mut guild_settings) = database.get_guild(guild.id).await? {
+        // Modify
+        guild_settings.date_order = order;
+        guild_settings
+    } else {
+        // Insert (shouldn't happen but ok)
+        let mut guild_settings = DbGuild::new(
+            guild.id,
+            default_channel(ctx.serenity_context(), &guild).await?,
+        );
+        guild_settings.date_order = order;
+        guild_settings
+    };
+
+    database.set_guild(guild_settings).await?;
+
+    ctx.say(tr!(
+        language,
+        "date-order-updated",
+        order = order
+            .localized_name(language.code())
+            .unwrap_or(order.name())
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Change whether reminders are sent as plain text or as an embed
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "verzending"),
+    description_localized(
+        "nl",
+        "Verander of herinneringen als gewone tekst of als embed verstuurd worden"
+    )
+)]
+pub async fn delivery(
+    ctx: Context<'_, Data, Error>,
+    #[description = "How reminders are sent"]
+    #[name_localized("nl", "verzending")]
+    #[description_localized("nl", "Hoe herinneringen verstuurd worden")]
+    delivery: Delivery,
+) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+
+    let guild = ctx.guild().ok_or("Not running in a guild")?;
+
+    let guild_settings = if let Some(mut guild_settings) = database.get_guild(guild.id).await? {
+        // Modify
+        guild_settings.delivery = delivery;
+        guild_settings
+    } else {
+        // Insert (shouldn't happen but ok)
+        let mut guild_settings = DbGuild::new(
+            guild.id,
+            default_channel(ctx.serenity_context(), &guild).await?,
+        );
+        guild_settings.delivery = delivery;
+        guild_settings
+    };
+
+    database.set_guild(guild_settings).await?;
+
+    ctx.say(tr!(
+        language,
+        "delivery-updated",
+        delivery = delivery
+            .localized_name(language.code())
+            .unwrap_or(delivery.name())
+    ))
+    .await?;
+
+    Ok(())
+}
+
+fn is_url(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+/// Change how reminder embeds look (use "none" to clear a setting)
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    description_localized(
+        "nl",
+        "Verander hoe de embeds van herinneringen eruitzien (gebruik \"geen\" om iets te wissen)"
+    )
+)]
+pub async fn embed(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Title, in the same format as /settings message"]
+    #[name_localized("nl", "titel")]
+    #[description_localized("nl", "Titel, in hetzelfde formaat als /instellingen bericht")]
+    title: Option<String>,
+    #[description = "Colour of the embed (for example \"#ff8800\")"]
+    #[name_localized("nl", "kleur")]
+    #[description_localized("nl", "Kleur van de embed (bijvoorbeeld \"#ff8800\")")]
+    colour: Option<String>,
+    #[description = "URL of a small image in the corner"]
+    #[name_localized("nl", "miniatuur")]
+    #[description_localized("nl", "URL van een kleine afbeelding in de hoek")]
+    thumbnail: Option<String>,
+    #[description = "URL of a large image under the message"]
+    #[name_localized("nl", "afbeelding")]
+    #[description_localized("nl", "URL van een grote afbeelding onder het bericht")]
+    image: Option<String>,
+) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+
+    let guild = ctx.guild().ok_or("Not running in a guild")?;
+
+    let mut guild_settings = if let Some(guild_settings) = database.get_guild(guild.id).await? {
+        guild_settings
+    } else {
+        // Insert (shouldn't happen but ok)
+        DbGuild::new(
+            guild.id,
+            default_channel(ctx.serenity_context(), &guild).await?,
+        )
+    };
+
+    if let Some(title) = title {
+        let title = optional_setting(title);
+        if let Some(Err(err)) = title.as_deref().map(Template::validate) {
+            ctx.say(tr!(language, "embed-title-invalid", error = err))
+                .await?;
+            return Ok(());
+        }
+        guild_settings.embed_title = title;
+    }
+    if let Some(colour) = colour {
+        guild_settings.embed_colour = match optional_setting(colour.clone()) {
+            Some(colour) => match u32::from_str_radix(colour.trim().trim_start_matches('#'), 16) {
+                Ok(colour) if colour <= 0xFFFFFF => Some(colour),
+                _ => {
+                    ctx.say(tr!(language, "invalid-colour", colour = colour))
+                        .await?;
+                    return Ok(());
+                }
+            },
+            None => None,
+        };
+    }
+    for (url, setting) in [
+        (thumbnail, &mut guild_settings.embed_thumbnail),
+        (image, &mut guild_settings.embed_image),
+    ] {
+        if let Some(url) = url {
+            let url = optional_setting(url);
+            if url.as_deref().is_some_and(|url| !is_url(url)) {
+                ctx.say(tr!(language, "invalid-url", url = url.unwrap()))
+                    .await?;
+                return Ok(());
+            }
+            *setting = url;
+        }
+    }
+
+    let delivery = guild_settings.delivery;
+    database.set_guild(guild_settings).await?;
+
+    if delivery == Delivery::Embed {
+        ctx.say(tr!(language, "embed-updated")).await?;
+    } else {
+        ctx.say(tr!(language, "embed-updated-hint")).await?;
+    }
+
+    Ok(())
+}
+
+/// Send a follow-up message on the day of each exam, asking how it went
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "vervolgbericht"),
+    description_localized(
+        "nl",
+        "Stuur op de dag van elk examen een vervolgbericht om te vragen hoe het ging"
+    )
+)]
+pub async fn followup(
+    ctx: Context<'_, Data, Error>,
+    #[description = "When to send it (24h notation, format: \"hour:minute\", \"none\" to turn follow-ups off)"]
+    #[name_localized("nl", "tijd")]
+    #[description_localized(
+        "nl",
+        "Wanneer het verstuurd wordt (formaat: \"uur:minuut\", \"geen\" om ze uit te zetten)"
+    )]
+    time: String,
+    #[description = "The message, in the same format as /settings message (\"none\" for the default)"]
+    #[name_localized("nl", "bericht")]
+    #[description_localized(
+        "nl",
+        "Het bericht, in hetzelfde formaat als /instellingen bericht (\"geen\" voor de standaard)"
+    )]
+    message: Option<String>,
+) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+
+    let guild = ctx.guild().ok_or("Not running in a guild")?;
+
+    let mut guild_settings = if let Some(guild_settings) = database.get_guild(guild.id).await? {
+        guild_settings
+    } else {
+        // Insert (shouldn't happen but ok)
+        DbGuild::new(
+            guild.id,
+            default_channel(ctx.serenity_context(), &guild).await?,
+        )
+    };
+
+    guild_settings.followup_time = match optional_setting(time) {
+        Some(time) => match NaiveTime::parse_from_str(&time, "%H:%M") {
+            Ok(time) => Some(time),
+            Err(_) => {
+                ctx.say(tr!(language, "invalid-time-format")).await?;
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+    if let Some(message) = message {
+        let message = optional_setting(message);
+        if let Some(Err(err)) = message.as_deref().map(Template::validate) {
+            ctx.say(tr!(language, "followup-invalid", error = err))
+                .await?;
+            return Ok(());
+        }
+        guild_settings.followup_format = message;
+    }
+
+    let followup_time = guild_settings.followup_time;
+    database.set_guild(guild_settings).await?;
+
+    if let Some(time) = followup_time {
+        ctx.say(tr!(
+            language,
+            "followup-updated",
+            time = time.format("%H:%M")
+        ))
+        .await?;
+    } else {
+        ctx.say(tr!(language, "followup-disabled")).await?;
+    }
+
+    // Follow-ups are scheduled together with the reminders
+    ctx.data().scheduler.load_exams_from_database().await?;
+
+    Ok(())
+}
+
+/// Keep a pinned message with a countdown to the next exams in the reminder channel
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "aftellen"),
+    description_localized(
+        "nl",
+        "Houd een vastgezet bericht bij dat aftelt tot de volgende examens in het herinneringskanaal"
+    )
+)]
+pub async fn countdown(
+    ctx: Context<'_, Data, Error>,
+    #[description = "How many exams it lists, leave empty to remove the countdown"]
+    #[name_localized("nl", "aantal")]
+    #[description_localized(
+        "nl",
+        "Hoeveel examens erin staan, laat leeg om het aftellen te verwijderen"
+    )]
+    #[min = 1]
+    #[max = 25]
+    size: Option<u32>,
+) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+
+    let guild = ctx.guild().ok_or("Not running in a guild")?;
+
+    let mut guild_settings = if let Some(guild_settings) = database.get_guild(guild.id).await? {
+        guild_settings
+    } else {
+        // Insert (shouldn't happen but ok)
+        DbGuild::new(
+            guild.id,
+            default_channel(ctx.serenity_context(), &guild).await?,
+        )
+    };
+    guild_settings.countdown_size = size.map(|size| size as i32);
+    let channel_id = guild_settings.message_channel_id;
+    database.set_guild(guild_settings).await?;
+
+    // Sends, edits or removes the message right away instead of at the next update
+    ctx.data().scheduler.update_countdown(guild.id).await?;
+
+    if let Some(size) = size {
+        ctx.say(tr!(
+            language,
+            "countdown-updated",
+            channel = channel_id.mention(),
+            count = size
+        ))
+        .await?;
+    } else {
+        ctx.say(tr!(language, "countdown-disabled")).await?;
+    }
+
+    Ok(())
+}
+
+/// Choose what happens to the exams of members who leave this server
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "vertrekkende_leden"),
+    description_localized(
+        "nl",
+        "Kies wat er gebeurt met de examens van leden die deze server verlaten"
+    )
+)]
+pub async fn member_leave(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Keep their exams, pause them until they come back, or delete them"]
+    #[name_localized("nl", "actie")]
+    #[description_localized(
+        "nl",
+        "Hun examens houden, pauzeren tot ze terugkomen, of verwijderen"
+    )]
+    action: MemberLeave,
+) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+
+    let guild = ctx.guild().ok_or("Not running in a guild")?;
+
+    let mut guild_settings = if let Some(guild_settings) = database.get_guild(guild.id).await? {
+        guild_settings
+    } else {
+        // Insert (shouldn't happen but ok)
+        DbGuild::new(
+            guild.id,
+            default_channel(ctx.serenity_context(), &guild).await?,
+        )
+    };
+    guild_settings.member_leave = action;
+    database.set_guild(guild_settings).await?;
+
+    let message = match action {
+        MemberLeave::Keep => tr!(language, "member-leave-keep"),
+        MemberLeave::Pause => tr!(language, "member-leave-pause"),
+        MemberLeave::Delete => tr!(language, "member-leave-delete"),
+    };
+    ctx.say(message).await?;
+
+    Ok(())
+}
+
+/// Send the reminders of each day in a thread under one message, so the channel stays readable
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "threads"),
+    description_localized(
+        "nl",
+        "Stuur de herinneringen van elke dag in een thread onder één bericht, zodat het kanaal leesbaar blijft"
+    )
+)]
+pub async fn threads(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Whether to use a thread per exam day"]
+    #[name_localized("nl", "aan")]
+    #[description_localized("nl", "Of er een thread per examendag gebruikt wordt")]
+    enabled: bool,
+) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
+    let database = &ctx.data().database;
+
+    let guild = ctx.guild().ok_or("Not running in a guild")?;
+
+    let mut guild_settings = if let Some(guild_settings) = database.get_guild(guild.id).await? {
+        guild_settings
+    } else {
+        // Insert (shouldn't happen but ok)
+        DbGuild::new(
+            guild.id,
+            default_channel(ctx.serenity_context(), &guild).await?,
+        )
+    };
+    guild_settings.day_threads = enabled;
+    database.set_guild(guild_settings).await?;
+
+    if enabled {
+        ctx.say(tr!(language, "threads-enabled")).await?;
+    } else {
+        ctx.say(tr!(language, "threads-disabled")).await?;
+    }
+
+    Ok(())
+}
+
+/// Change the language the bot replies and sends reminders in
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "taal"),
+    description_localized(
+        "nl",
+        "Verander de taal waarin de bot antwoordt en herinneringen stuurt"
+    )
+)]
+pub async fn language(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Language to use, leave empty to reply in the Discord language of each user"]
+    #[name_localized("nl", "taal")]
+    #[description_localized(
+        "nl",
+        "Taal om te gebruiken, laat leeg om te antwoorden in de Discord-taal van elke gebruiker"
+    )]
+    language: Option<Language>,
+) -> Result<(), Error> {
+    let database = &ctx.data().database;
+
+    let guild = ctx.guild().ok_or("Not running in a guild")?;
+
+    let guild_settings = if let Some(mut guild_settings) = database.get_guild(guild.id).await? {
+        // Modify
+        guild_settings.language = language;
+        guild_settings
+    } else {
+        // Insert (shouldn't happen but ok)
+        let mut guild_settings = DbGuild::new(
+            guild.id,
+            default_channel(ctx.serenity_context(), &guild).await?,
+        );
+        guild_settings.language = language;
+        guild_settings
+    };
+
+    database.set_guild(guild_settings).await?;
+
+    // Reply in the new language
+    if let Some(language) = language {
+        ctx.say(tr!(language, "language-updated")).await?;
+    } else {
+        ctx.say(tr!(i18n::language(ctx).await?, "language-reset"))
+            .await?;
+    }
+
+    Ok(())
+}
+
 /// Change the time at which this bot sends messages
-#[poise::command(slash_command, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    name_localized("nl", "overzicht"),
+    description_localized("nl", "Toon de instellingen van de bot voor deze server")
+)]
 pub async fn list(ctx: Context<'_, Data, Error>) -> Result<(), Error> {
+    let language = i18n::language(ctx).await?;
     let database = &ctx.data().database;
     let guild = ctx.guild().ok_or("Not running in a guild")?;
 
     let guild_settings = if let Some(guild_settings) = database.get_guild(guild.id).await? {
         guild_settings
     } else {
-        ctx.say(format!("No settings saved for this guild."))
-            .await?;
+        ctx.say(tr!(language, "settings-none")).await?;
         return Ok(());
     };
 
-    ctx.say(format!(
-        "**Settings**:\nChannel: {}\nTime: {} {}\nFormat: {}",
-        guild_settings.message_channel_id.mention(),
-        guild_settings.message_time,
-        guild_settings.message_timezone,
-        guild_settings.format
+    let order = guild_settings.date_order;
+    let delivery = guild_settings.delivery;
+    let member_leave = guild_settings.member_leave;
+    ctx.say(tr!(
+        language,
+        "settings-list",
+        channel = guild_settings.message_channel_id.mention(),
+        time = guild_settings.message_time,
+        timezone = guild_settings.message_timezone,
+        format = guild_settings
+            .format
+            .as_deref()
+            .unwrap_or(default_format(language)),
+        locales = locale::format_locales(&guild_settings.parse_locales),
+        order = order
+            .localized_name(language.code())
+            .unwrap_or(order.name()),
+        delivery = delivery
+            .localized_name(language.code())
+            .unwrap_or(delivery.name()),
+        language = guild_settings
+            .language
+            .map_or(tr!(language, "language-default"), |language| {
+                language.name().to_owned()
+            }),
+        followup = guild_settings
+            .followup_time
+            .map_or(tr!(language, "setting-off"), |time| time
+                .format("%H:%M")
+                .to_string()),
+        countdown = guild_settings
+            .countdown_size
+            .map_or(tr!(language, "setting-off"), |size| size.to_string()),
+        member_leave = member_leave
+            .localized_name(language.code())
+            .unwrap_or(member_leave.name()),
+        threads = if guild_settings.day_threads {
+            tr!(language, "setting-on")
+        } else {
+            tr!(language, "setting-off")
+        }
     ))
     .await?;
 
@@ -0,0 +1,151 @@
+// Time left until exams, and the live countdown message the scheduler keeps up to date
+
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use chrono_tz::Tz;
+use log::warn;
+use poise::serenity_prelude::{Context, GuildId};
+
+use crate::{
+    database::{Database, DbExam},
+    formatter::{mention_owner, DISCORD_MESSAGE_LENGTH},
+    i18n::{self, tr, Language},
+    scheduler::calculate_schedule_time,
+    Error,
+};
+
+// Exams without a time count down to the start of their day
+pub fn exam_start(exam: &DbExam, timezone: Tz) -> DateTime<Utc> {
+    calculate_schedule_time(exam.day, exam.time.unwrap_or(NaiveTime::MIN), timezone)
+}
+
+pub fn format_countdown(left: Duration, language: Language) -> String {
+    if left < Duration::hours(1) {
+        tr!(language, "countdown-soon")
+    } else {
+        tr!(
+            language,
+            "countdown-duration",
+            days = left.num_days(),
+            hours = left.num_hours() % 24
+        )
+    }
+}
+
+// Adds the lines to the message as long as it stays within Discord's message limit
+pub fn push_lines(message: &mut String, lines: impl IntoIterator<Item = String>) {
+    let mut length = message.chars().count();
+    for line in lines {
+        length += 1 + line.chars().count();
+        if length > DISCORD_MESSAGE_LENGTH {
+            break;
+        }
+        message.push('\n');
+        message.push_str(&line);
+    }
+}
+
+// Exams that didn't start yet, soonest first
+pub fn upcoming_exams(
+    exams: Vec<DbExam>,
+    timezone: Tz,
+    now: DateTime<Utc>,
+) -> Vec<(DbExam, Duration)> {
+    let mut upcoming: Vec<_> = exams
+        .into_iter()
+        .map(|exam| {
+            let left = exam_start(&exam, timezone) - now;
+            (exam, left)
+        })
+        .filter(|(_, left)| *left > Duration::zero())
+        .collect();
+    upcoming.sort_unstable_by_key(|(_, left)| *left);
+    upcoming
+}
+
+fn format_live_countdown(exams: Vec<(DbExam, Duration)>, language: Language) -> String {
+    let mut message = tr!(language, "countdown-live-header");
+    if exams.is_empty() {
+        message.push('\n');
+        message.push_str(&tr!(language, "countdown-live-empty"));
+    }
+    let lines = exams.into_iter().map(|(exam, left)| {
+        let owner = mention_owner(&exam, language);
+        let countdown = format_countdown(left, language);
+        if exam.exam_name.is_empty() {
+            format!("\t{} - {}: {}", owner, exam.day, countdown)
+        } else {
+            format!(
+                "\t{} - {} - {}: {}",
+                owner, exam.day, exam.exam_name, countdown
+            )
+        }
+    });
+    push_lines(&mut message, lines);
+    message
+}
+
+// Edits the guild's countdown message, or sends and pins a new one if that's not possible
+pub async fn update_message(
+    ctx: &Context,
+    database: &Database,
+    guild_id: GuildId,
+) -> Result<(), Error> {
+    let guild = if let Some(guild) = database.get_guild(guild_id).await? {
+        guild
+    } else {
+        return Ok(());
+    };
+    let existing = database.get_countdown_message(guild_id).await?;
+
+    let size = if let Some(size) = guild.countdown_size {
+        size
+    } else {
+        // Turned off
+        if let Some((channel_id, message_id)) = existing {
+            let _ = channel_id.delete_message(ctx, message_id).await;
+            database.delete_countdown_message(guild_id).await?;
+        }
+        return Ok(());
+    };
+
+    let language = i18n::guild_language(&guild, &ctx.cache);
+    let now = Utc::now();
+    let today = now.with_timezone(&guild.message_timezone).date_naive();
+    let exams = database.get_guild_countdown_exams(guild_id, today).await?;
+    let mut exams = upcoming_exams(exams, guild.message_timezone, now);
+    exams.truncate(size as usize);
+    let content = format_live_countdown(exams, language);
+
+    if let Some((channel_id, message_id)) = existing {
+        if channel_id == guild.message_channel_id {
+            match channel_id
+                .edit_message(ctx, message_id, |m| m.content(&content))
+                .await
+            {
+                Ok(_) => return Ok(()),
+                Err(err) => warn!(
+                    "Couldn't edit countdown message in {}, sending a new one: {}",
+                    guild_id, err
+                ),
+            }
+        } else {
+            // The reminder channel changed
+            let _ = channel_id.delete_message(ctx, message_id).await;
+        }
+    }
+
+    // Nobody gets pinged by the countdown
+    let message = guild
+        .message_channel_id
+        .send_message(ctx, |m| {
+            m.content(&content)
+                .allowed_mentions(|mentions| mentions.empty_parse())
+        })
+        .await?;
+    if let Err(err) = message.pin(ctx).await {
+        warn!("Couldn't pin countdown message in {}: {}", guild_id, err);
+    }
+    database
+        .set_countdown_message(guild_id, message.channel_id, message.id)
+        .await
+}
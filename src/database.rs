@@ -1,15 +1,22 @@
 use std::str::FromStr;
 
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use log::info;
-use poise::serenity_prelude::{ChannelId, GuildId, UserId};
+use poise::serenity_prelude::{ChannelId, GuildId, MessageId, RoleId, UserId};
 use serenity::prelude::TypeMapKey;
 use sqlx::{
     postgres::{PgConnectOptions, PgPoolOptions},
     Pool, Postgres,
 };
 
-use crate::Error;
+use crate::{
+    followup::FollowUpResult,
+    formatter::{Delivery, MessagePool},
+    i18n::Language,
+    locale::{self, Locale},
+    schedule_parser::DateOrder,
+    Error,
+};
 
 // Should be safe to clone right now
 // If new fields ever get added here in addition to pool, make sure they're fine to clone as well!
@@ -18,13 +25,85 @@ pub struct Database {
     pool: Pool<Postgres>,
 }
 
+// What happens to the exams of a member who leaves the guild
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum MemberLeave {
+    #[default]
+    #[name = "keep"]
+    #[name_localized("nl", "houden")]
+    Keep,
+    // No messages until the member comes back
+    #[name = "pause"]
+    #[name_localized("nl", "pauzeren")]
+    Pause,
+    #[name = "delete"]
+    #[name_localized("nl", "verwijderen")]
+    Delete,
+}
+
+impl MemberLeave {
+    // Name stored in the database
+    pub fn code(&self) -> &'static str {
+        match self {
+            MemberLeave::Keep => "keep",
+            MemberLeave::Pause => "pause",
+            MemberLeave::Delete => "delete",
+        }
+    }
+}
+
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct DbGuild {
     pub guild_id: GuildId,
     pub message_channel_id: ChannelId,
     pub message_time: chrono::NaiveTime,
     pub message_timezone: chrono_tz::Tz,
-    pub format: String,
+    // None uses the default format in the guild's language
+    pub format: Option<String>,
+    pub parse_locales: Vec<&'static Locale>,
+    pub date_order: DateOrder,
+    pub delivery: Delivery,
+    // Only used when delivering reminders as embeds
+    pub embed_title: Option<String>,
+    pub embed_colour: Option<u32>,
+    pub embed_thumbnail: Option<String>,
+    pub embed_image: Option<String>,
+    // None replies in the language of the user
+    pub language: Option<Language>,
+    // Follow-ups are only sent when there's a time
+    pub followup_time: Option<NaiveTime>,
+    pub followup_format: Option<String>,
+    // Number of exams in the live countdown, None means there's none
+    pub countdown_size: Option<i32>,
+    pub member_leave: MemberLeave,
+    // Reminders for the same day are sent in one thread
+    pub day_threads: bool,
+}
+
+impl DbGuild {
+    // Default settings for a guild the bot just joined
+    pub fn new(guild_id: GuildId, message_channel_id: ChannelId) -> Self {
+        DbGuild {
+            guild_id,
+            message_channel_id,
+            message_time: NaiveTime::from_hms_opt(21, 0, 0).unwrap(),
+            message_timezone: chrono_tz::UTC,
+            format: None,
+            parse_locales: locale::parse_locales(locale::DEFAULT_LOCALES).unwrap(),
+            date_order: DateOrder::default(),
+            delivery: Delivery::default(),
+            embed_title: None,
+            embed_colour: None,
+            embed_thumbnail: None,
+            embed_image: None,
+            language: None,
+            followup_time: None,
+            followup_format: None,
+            countdown_size: None,
+            member_leave: MemberLeave::default(),
+            day_threads: false,
+        }
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, Debug)]
@@ -34,6 +113,65 @@ pub struct DbExam {
     pub guild_id: GuildId,
     pub day: NaiveDate,
     pub exam_name: String,
+    pub time: Option<NaiveTime>,
+    // Used instead of the guild's format and messages
+    pub message: Option<String>,
+    // Whether the reminder was sent, the exam is kept until its follow-up is sent
+    pub reminded: bool,
+    // Taken by the exam's participants instead of user_id
+    pub group: bool,
+    // Taken by every member with this role instead of user_id
+    pub role: Option<RoleId>,
+}
+
+// Copy of an exam that got a follow-up, with the user's answer
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct DbExamResult {
+    pub exam_id: i64,
+    pub guild_id: GuildId,
+    pub user_id: UserId,
+    pub day: NaiveDate,
+    pub exam_name: String,
+    pub result: Option<FollowUpResult>,
+}
+
+// Exam period of a guild, like the January exams
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct DbSession {
+    pub session_id: i64,
+    pub guild_id: GuildId,
+    pub name: String,
+    pub start_day: NaiveDate,
+    pub end_day: NaiveDate,
+    pub start_announced: bool,
+    pub end_announced: bool,
+}
+
+// Exam of a session, which is done once its last message was sent
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct DbSessionExam {
+    pub day: NaiveDate,
+    pub exam_name: String,
+    pub done: bool,
+}
+
+// Message with a thread that the reminders of one day are sent in
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct DbDayThread {
+    pub guild_id: GuildId,
+    pub day: NaiveDate,
+    pub channel_id: ChannelId,
+    pub message_id: MessageId,
+    pub thread_id: ChannelId,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct DbMessage {
+    pub message_id: i64,
+    pub guild_id: GuildId,
+    pub pool: MessagePool,
+    pub format: String,
+    pub weight: i32,
 }
 
 impl Database {
@@ -52,6 +190,24 @@ impl Database {
                 message_time: guild.message_time,
                 message_timezone: guild.message_timezone.parse::<chrono_tz::Tz>()?,
                 format: guild.format,
+                // Unknown locales are skipped rather than failing to load the guild
+                parse_locales: guild
+                    .parse_locales
+                    .split(',')
+                    .filter_map(locale::find_locale)
+                    .collect(),
+                date_order: guild.date_order.parse().unwrap_or_default(),
+                delivery: guild.delivery.parse().unwrap_or_default(),
+                embed_title: guild.embed_title,
+                embed_colour: guild.embed_colour.map(|colour| colour as u32),
+                embed_thumbnail: guild.embed_thumbnail,
+                embed_image: guild.embed_image,
+                language: guild.language.and_then(|language| language.parse().ok()),
+                followup_time: guild.followup_time,
+                followup_format: guild.followup_format,
+                countdown_size: guild.countdown_size,
+                member_leave: guild.member_leave.parse().unwrap_or_default(),
+                day_threads: guild.day_threads,
             }))
         } else {
             Ok(None)
@@ -61,14 +217,28 @@ impl Database {
     pub async fn set_guild(&self, guild: DbGuild) -> Result<(), Error> {
         let message_time = guild.message_time;
         let message_timezone = guild.message_timezone.to_string();
+        let parse_locales = locale::format_locales(&guild.parse_locales);
         sqlx::query!(
-            "INSERT INTO guilds(guild_id, message_channel_id, message_time, message_timezone, format) VALUES($1, $2, $3, $4, $5)
-        ON CONFLICT(guild_id) DO UPDATE SET message_channel_id=excluded.message_channel_id, message_time=excluded.message_time, message_timezone=excluded.message_timezone, format=excluded.format;",
+            "INSERT INTO guilds(guild_id, message_channel_id, message_time, message_timezone, format, parse_locales, date_order, delivery, embed_title, embed_colour, embed_thumbnail, embed_image, language, followup_time, followup_format, countdown_size, member_leave, day_threads) VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+        ON CONFLICT(guild_id) DO UPDATE SET message_channel_id=excluded.message_channel_id, message_time=excluded.message_time, message_timezone=excluded.message_timezone, format=excluded.format, parse_locales=excluded.parse_locales, date_order=excluded.date_order, delivery=excluded.delivery, embed_title=excluded.embed_title, embed_colour=excluded.embed_colour, embed_thumbnail=excluded.embed_thumbnail, embed_image=excluded.embed_image, language=excluded.language, followup_time=excluded.followup_time, followup_format=excluded.followup_format, countdown_size=excluded.countdown_size, member_leave=excluded.member_leave, day_threads=excluded.day_threads;",
             guild.guild_id.0 as i64,
             guild.message_channel_id.0 as i64,
             message_time,
             message_timezone,
-            guild.format
+            guild.format,
+            parse_locales,
+            guild.date_order.code(),
+            guild.delivery.code(),
+            guild.embed_title,
+            guild.embed_colour.map(|colour| colour as i32),
+            guild.embed_thumbnail,
+            guild.embed_image,
+            guild.language.map(|language| language.code()),
+            guild.followup_time,
+            guild.followup_format,
+            guild.countdown_size,
+            guild.member_leave.code(),
+            guild.day_threads
         )
         .execute(&self.pool)
         .await?;
@@ -76,11 +246,198 @@ impl Database {
         Ok(())
     }
 
-    pub async fn get_all_exams(&self) -> Result<Vec<DbExam>, Error> {
-        let exams: Vec<_> = sqlx::query!("SELECT * FROM exams")
+    pub async fn get_guild_ids(&self) -> Result<Vec<GuildId>, Error> {
+        let guilds = sqlx::query!("SELECT guild_id FROM guilds")
             .fetch_all(&self.pool)
             .await?;
 
+        Ok(guilds
+            .into_iter()
+            .map(|guild| GuildId(guild.guild_id as u64))
+            .collect())
+    }
+
+    // Keeps the time the bot left first, if it's marked as left again
+    pub async fn set_guild_left(&self, guild_id: GuildId) -> Result<(), Error> {
+        sqlx::query!(
+            "UPDATE guilds SET left_at = COALESCE(left_at, now()) WHERE guild_id = $1;",
+            guild_id.0 as i64
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // Returns false if the guild wasn't marked as left
+    pub async fn set_guild_returned(&self, guild_id: GuildId) -> Result<bool, Error> {
+        let updated = sqlx::query!(
+            "UPDATE guilds SET left_at = NULL WHERE guild_id = $1 AND left_at IS NOT NULL;",
+            guild_id.0 as i64
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(updated.rows_affected() > 0)
+    }
+
+    // Deletes everything of guilds the bot left before the given time, returns how many there were
+    pub async fn delete_left_guilds(&self, before: DateTime<Utc>) -> Result<u64, Error> {
+        let deleted = sqlx::query!("DELETE FROM guilds WHERE left_at < $1;", before)
+            .execute(&self.pool)
+            .await?;
+        Ok(deleted.rows_affected())
+    }
+
+    // Only the user's own exams, not the group and role exams they created.
+    // Returns the ids of the exams that changed.
+    pub async fn set_user_exams_paused(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        paused: bool,
+    ) -> Result<Vec<i64>, Error> {
+        let updated = sqlx::query!(
+            "UPDATE exams SET paused = $3 WHERE guild_id = $1 AND user_id = $2 AND NOT group_exam AND role_id IS NULL AND paused != $3 RETURNING exam_id;",
+            guild_id.0 as i64,
+            user_id.0 as i64,
+            paused
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(updated.into_iter().map(|exam| exam.exam_id).collect())
+    }
+
+    // Deletes the user's own exams and takes them out of group exams
+    pub async fn delete_user_exams(&self, guild_id: GuildId, user_id: UserId) -> Result<(), Error> {
+        sqlx::query!(
+            "DELETE FROM exams WHERE guild_id = $1 AND user_id = $2 AND NOT group_exam AND role_id IS NULL;",
+            guild_id.0 as i64,
+            user_id.0 as i64
+        )
+        .execute(&self.pool)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM exam_participants WHERE user_id = $2 AND exam_id IN (SELECT exam_id FROM exams WHERE guild_id = $1);",
+            guild_id.0 as i64,
+            user_id.0 as i64
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_day_thread(
+        &self,
+        guild_id: GuildId,
+        day: NaiveDate,
+    ) -> Result<Option<DbDayThread>, Error> {
+        let day_thread = sqlx::query!(
+            "SELECT * FROM day_threads WHERE guild_id = $1 AND day = $2",
+            guild_id.0 as i64,
+            day
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(day_thread.map(|day_thread| DbDayThread {
+            guild_id: GuildId(day_thread.guild_id as u64),
+            day: day_thread.day,
+            channel_id: ChannelId(day_thread.channel_id as u64),
+            message_id: MessageId(day_thread.message_id as u64),
+            thread_id: ChannelId(day_thread.thread_id as u64),
+        }))
+    }
+
+    pub async fn set_day_thread(&self, day_thread: DbDayThread) -> Result<(), Error> {
+        sqlx::query!(
+            "INSERT INTO day_threads(guild_id, day, channel_id, message_id, thread_id) VALUES($1, $2, $3, $4, $5)
+            ON CONFLICT(guild_id, day) DO UPDATE SET channel_id=excluded.channel_id, message_id=excluded.message_id, thread_id=excluded.thread_id;",
+            day_thread.guild_id.0 as i64,
+            day_thread.day,
+            day_thread.channel_id.0 as i64,
+            day_thread.message_id.0 as i64,
+            day_thread.thread_id.0 as i64
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_day_threads_before(&self, day: NaiveDate) -> Result<(), Error> {
+        sqlx::query!("DELETE FROM day_threads WHERE day < $1;", day)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_countdown_guilds(&self) -> Result<Vec<GuildId>, Error> {
+        let guilds = sqlx::query!(
+            "SELECT guild_id FROM guilds WHERE countdown_size IS NOT NULL AND left_at IS NULL"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(guilds
+            .into_iter()
+            .map(|guild| GuildId(guild.guild_id as u64))
+            .collect())
+    }
+
+    pub async fn get_countdown_message(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Option<(ChannelId, MessageId)>, Error> {
+        let message = sqlx::query!(
+            "SELECT channel_id, message_id FROM countdown_messages WHERE guild_id = $1",
+            guild_id.0 as i64
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(message.map(|message| {
+            (
+                ChannelId(message.channel_id as u64),
+                MessageId(message.message_id as u64),
+            )
+        }))
+    }
+
+    pub async fn set_countdown_message(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            "INSERT INTO countdown_messages(guild_id, channel_id, message_id) VALUES($1, $2, $3)
+            ON CONFLICT(guild_id) DO UPDATE SET channel_id=excluded.channel_id, message_id=excluded.message_id;",
+            guild_id.0 as i64,
+            channel_id.0 as i64,
+            message_id.0 as i64
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_countdown_message(&self, guild_id: GuildId) -> Result<(), Error> {
+        sqlx::query!(
+            "DELETE FROM countdown_messages WHERE guild_id = $1;",
+            guild_id.0 as i64
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_all_exams(&self) -> Result<Vec<DbExam>, Error> {
+        // Nothing is sent for paused exams and guilds the bot left
+        let exams: Vec<_> = sqlx::query!(
+            "SELECT * FROM exams WHERE NOT done AND NOT paused
+                AND guild_id IN (SELECT guild_id FROM guilds WHERE left_at IS NULL)"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
         let exams = exams
             .into_iter()
             .map(|exam| DbExam {
@@ -89,6 +446,11 @@ impl Database {
                 guild_id: GuildId(exam.guild_id as u64),
                 day: exam.day,
                 exam_name: exam.exam_name,
+                time: exam.exam_time,
+                message: exam.message,
+                reminded: exam.reminded,
+                group: exam.group_exam,
+                role: exam.role_id.map(|role_id| RoleId(role_id as u64)),
             })
             .collect();
 
@@ -96,10 +458,12 @@ impl Database {
     }
 
     pub async fn get_guild_exams(&self, guild_id: GuildId) -> Result<Vec<DbExam>, Error> {
-        let exams: Vec<_> =
-            sqlx::query!("SELECT * FROM exams WHERE guild_id = $1", guild_id.0 as i64)
-                .fetch_all(&self.pool)
-                .await?;
+        let exams: Vec<_> = sqlx::query!(
+            "SELECT * FROM exams WHERE guild_id = $1 AND NOT done",
+            guild_id.0 as i64
+        )
+        .fetch_all(&self.pool)
+        .await?;
 
         let exams = exams
             .into_iter()
@@ -109,6 +473,11 @@ impl Database {
                 guild_id: GuildId(exam.guild_id as u64),
                 day: exam.day,
                 exam_name: exam.exam_name,
+                time: exam.exam_time,
+                message: exam.message,
+                reminded: exam.reminded,
+                group: exam.group_exam,
+                role: exam.role_id.map(|role_id| RoleId(role_id as u64)),
             })
             .collect();
 
@@ -126,6 +495,11 @@ impl Database {
             guild_id: GuildId(exam.guild_id as u64),
             day: exam.day,
             exam_name: exam.exam_name,
+            time: exam.exam_time,
+            message: exam.message,
+            reminded: exam.reminded,
+            group: exam.group_exam,
+            role: exam.role_id.map(|role_id| RoleId(role_id as u64)),
         });
         Ok(exam)
     }
@@ -136,7 +510,10 @@ impl Database {
         user_id: UserId,
     ) -> Result<Vec<DbExam>, Error> {
         let exams: Vec<_> = sqlx::query!(
-            "SELECT * FROM exams WHERE guild_id = $1 AND user_id = $2",
+            "SELECT * FROM exams WHERE guild_id = $1 AND NOT done AND (
+                (NOT group_exam AND role_id IS NULL AND user_id = $2)
+                OR exam_id IN (SELECT exam_id FROM exam_participants WHERE user_id = $2)
+            )",
             guild_id.0 as i64,
             user_id.0 as i64
         )
@@ -151,6 +528,87 @@ impl Database {
                 guild_id: GuildId(exam.guild_id as u64),
                 day: exam.day,
                 exam_name: exam.exam_name,
+                time: exam.exam_time,
+                message: exam.message,
+                reminded: exam.reminded,
+                group: exam.group_exam,
+                role: exam.role_id.map(|role_id| RoleId(role_id as u64)),
+            })
+            .collect();
+
+        Ok(exams)
+    }
+
+    // Exams on or after `since` for the countdown, including those whose reminders were already sent.
+    // Paused exams are left out, like in get_all_exams.
+    pub async fn get_guild_countdown_exams(
+        &self,
+        guild_id: GuildId,
+        since: NaiveDate,
+    ) -> Result<Vec<DbExam>, Error> {
+        let exams: Vec<_> = sqlx::query!(
+            "SELECT * FROM exams WHERE guild_id = $1 AND day >= $2 AND NOT paused",
+            guild_id.0 as i64,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let exams = exams
+            .into_iter()
+            .map(|exam| DbExam {
+                exam_id: exam.exam_id,
+                user_id: UserId(exam.user_id as u64),
+                guild_id: GuildId(exam.guild_id as u64),
+                day: exam.day,
+                exam_name: exam.exam_name,
+                time: exam.exam_time,
+                message: exam.message,
+                reminded: exam.reminded,
+                group: exam.group_exam,
+                role: exam.role_id.map(|role_id| RoleId(role_id as u64)),
+            })
+            .collect();
+
+        Ok(exams)
+    }
+
+    // Like get_guild_countdown_exams, but only the exams the user takes, including those of the given roles
+    pub async fn get_user_countdown_exams(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        roles: &[RoleId],
+        since: NaiveDate,
+    ) -> Result<Vec<DbExam>, Error> {
+        let roles: Vec<i64> = roles.iter().map(|role_id| role_id.0 as i64).collect();
+        let exams: Vec<_> = sqlx::query!(
+            "SELECT * FROM exams WHERE guild_id = $1 AND day >= $4 AND NOT paused AND (
+                (NOT group_exam AND role_id IS NULL AND user_id = $2)
+                OR exam_id IN (SELECT exam_id FROM exam_participants WHERE user_id = $2)
+                OR role_id = ANY($3)
+            )",
+            guild_id.0 as i64,
+            user_id.0 as i64,
+            &roles,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let exams = exams
+            .into_iter()
+            .map(|exam| DbExam {
+                exam_id: exam.exam_id,
+                user_id: UserId(exam.user_id as u64),
+                guild_id: GuildId(exam.guild_id as u64),
+                day: exam.day,
+                exam_name: exam.exam_name,
+                time: exam.exam_time,
+                message: exam.message,
+                reminded: exam.reminded,
+                group: exam.group_exam,
+                role: exam.role_id.map(|role_id| RoleId(role_id as u64)),
             })
             .collect();
 
@@ -160,17 +618,256 @@ impl Database {
     // Inserts a DbExam, ignoring the exam_id
     pub async fn insert_exam(&self, exam: DbExam) -> Result<i64, sqlx::Error> {
         let ret = sqlx::query!(
-            "INSERT INTO exams(user_id, guild_id, day, exam_name) VALUES($1, $2, $3, $4) RETURNING exam_id;",
+            "INSERT INTO exams(user_id, guild_id, day, exam_name, exam_time, message, group_exam, role_id, session_id) VALUES($1, $2, $3, $4, $5, $6, $7, $8,
+                (SELECT session_id FROM sessions WHERE guild_id = $2 AND $3 BETWEEN start_day AND end_day)) RETURNING exam_id;",
             exam.user_id.0 as i64,
             exam.guild_id.0 as i64,
             exam.day,
-            exam.exam_name
+            exam.exam_name,
+            exam.time,
+            exam.message,
+            exam.group,
+            exam.role.map(|role_id| role_id.0 as i64)
         )
         .fetch_one(&self.pool)
         .await?;
         Ok(ret.exam_id)
     }
 
+    // Updates everything but the user and guild of a DbExam
+    // Moving an exam to another day means it gets a reminder again, and maybe another session
+    pub async fn update_exam(&self, exam: DbExam) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE exams SET day = $1, exam_name = $2, exam_time = $3, message = $4, reminded = (reminded AND day = $1), done = (done AND day = $1),
+                session_id = (SELECT session_id FROM sessions WHERE sessions.guild_id = exams.guild_id AND $1 BETWEEN start_day AND end_day) WHERE exam_id = $5;",
+            exam.day,
+            exam.exam_name,
+            exam.time,
+            exam.message,
+            exam.exam_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_guild_messages(&self, guild_id: GuildId) -> Result<Vec<DbMessage>, Error> {
+        let messages = sqlx::query!(
+            "SELECT * FROM messages WHERE guild_id = $1 ORDER BY message_id",
+            guild_id.0 as i64
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let messages = messages
+            .into_iter()
+            .map(|message| DbMessage {
+                message_id: message.message_id,
+                guild_id: GuildId(message.guild_id as u64),
+                pool: message.pool.parse().unwrap_or_default(),
+                format: message.format,
+                weight: message.weight,
+            })
+            .collect();
+
+        Ok(messages)
+    }
+
+    // Inserts a DbMessage, ignoring the message_id
+    pub async fn insert_message(&self, message: DbMessage) -> Result<i64, Error> {
+        let ret = sqlx::query!(
+            "INSERT INTO messages(guild_id, pool, format, weight) VALUES($1, $2, $3, $4) RETURNING message_id;",
+            message.guild_id.0 as i64,
+            message.pool.code(),
+            message.format,
+            message.weight
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(ret.message_id)
+    }
+
+    // Returns whether the message existed in this guild
+    pub async fn delete_message(&self, guild_id: GuildId, message_id: i64) -> Result<bool, Error> {
+        let result = sqlx::query!(
+            "DELETE FROM messages WHERE guild_id = $1 AND message_id = $2;",
+            guild_id.0 as i64,
+            message_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_last_message(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<i64>, Error> {
+        let last = sqlx::query!(
+            "SELECT message_id FROM last_messages WHERE guild_id = $1 AND user_id = $2",
+            guild_id.0 as i64,
+            user_id.0 as i64
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(last.map(|last| last.message_id))
+    }
+
+    pub async fn set_last_message(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        message_id: i64,
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            "INSERT INTO last_messages(guild_id, user_id, message_id) VALUES($1, $2, $3)
+        ON CONFLICT(guild_id, user_id) DO UPDATE SET message_id=excluded.message_id;",
+            guild_id.0 as i64,
+            user_id.0 as i64,
+            message_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // Moves an exam to another day
+    pub async fn update_exam_day(&self, exam_id: i64, day: NaiveDate) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE exams SET day = $1, reminded = (reminded AND day = $1), done = (done AND day = $1),
+                session_id = (SELECT session_id FROM sessions WHERE sessions.guild_id = exams.guild_id AND $1 BETWEEN start_day AND end_day) WHERE exam_id = $2;",
+            day,
+            exam_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_exam_reminded(&self, exam_id: i64) -> Result<(), Error> {
+        sqlx::query!(
+            "UPDATE exams SET reminded = TRUE WHERE exam_id = $1;",
+            exam_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_exam_participants(&self, exam_id: i64) -> Result<Vec<UserId>, Error> {
+        let participants = sqlx::query!(
+            "SELECT user_id FROM exam_participants WHERE exam_id = $1 ORDER BY user_id",
+            exam_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(participants
+            .into_iter()
+            .map(|participant| UserId(participant.user_id as u64))
+            .collect())
+    }
+
+    // Everyone who takes the exam
+    pub async fn get_exam_users(&self, exam: &DbExam) -> Result<Vec<UserId>, Error> {
+        if exam.group {
+            self.get_exam_participants(exam.exam_id).await
+        } else {
+            Ok(vec![exam.user_id])
+        }
+    }
+
+    // Returns false if the user already participated
+    pub async fn add_exam_participant(&self, exam_id: i64, user_id: UserId) -> Result<bool, Error> {
+        let inserted = sqlx::query!(
+            "INSERT INTO exam_participants(exam_id, user_id) VALUES($1, $2) ON CONFLICT DO NOTHING;",
+            exam_id,
+            user_id.0 as i64
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(inserted.rows_affected() > 0)
+    }
+
+    // Returns false if the user didn't participate
+    pub async fn remove_exam_participant(
+        &self,
+        exam_id: i64,
+        user_id: UserId,
+    ) -> Result<bool, Error> {
+        let deleted = sqlx::query!(
+            "DELETE FROM exam_participants WHERE exam_id = $1 AND user_id = $2;",
+            exam_id,
+            user_id.0 as i64
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(deleted.rows_affected() > 0)
+    }
+
+    // Keeps a copy of the exam to store the user's answer to its follow-up in
+    pub async fn insert_exam_result(&self, exam: &DbExam, user_id: UserId) -> Result<(), Error> {
+        sqlx::query!(
+            "INSERT INTO exam_results(exam_id, guild_id, user_id, day, exam_name) VALUES($1, $2, $3, $4, $5)
+        ON CONFLICT(exam_id, user_id) DO NOTHING;",
+            exam.exam_id,
+            exam.guild_id.0 as i64,
+            user_id.0 as i64,
+            exam.day,
+            exam.exam_name
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // Only the user who took the exam can answer, returns whether the answer was saved
+    pub async fn set_exam_result(
+        &self,
+        exam_id: i64,
+        user_id: UserId,
+        result: FollowUpResult,
+    ) -> Result<bool, Error> {
+        let updated = sqlx::query!(
+            "UPDATE exam_results SET result = $1 WHERE exam_id = $2 AND user_id = $3;",
+            result.code(),
+            exam_id,
+            user_id.0 as i64
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(updated.rows_affected() > 0)
+    }
+
+    pub async fn get_user_results(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<DbExamResult>, Error> {
+        let results = sqlx::query!(
+            "SELECT * FROM exam_results WHERE guild_id = $1 AND user_id = $2 ORDER BY day",
+            guild_id.0 as i64,
+            user_id.0 as i64
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let results = results
+            .into_iter()
+            .map(|result| DbExamResult {
+                exam_id: result.exam_id,
+                guild_id: GuildId(result.guild_id as u64),
+                user_id: UserId(result.user_id as u64),
+                day: result.day,
+                exam_name: result.exam_name,
+                result: result.result.and_then(|result| result.parse().ok()),
+            })
+            .collect();
+
+        Ok(results)
+    }
+
     // Deletes a DbExam
     pub async fn delete_exam(&self, exam_id: i64) -> Result<(), Error> {
         sqlx::query!("DELETE FROM exams WHERE exam_id=$1;", exam_id)
@@ -178,6 +875,215 @@ impl Database {
             .await?;
         Ok(())
     }
+
+    // Finished exams stay in the countdown until their day has passed, see delete_finished_exams
+    pub async fn finish_exam(&self, exam_id: i64) -> Result<(), Error> {
+        sqlx::query!("UPDATE exams SET done = TRUE WHERE exam_id = $1;", exam_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // Exams of a session are kept to count them as done
+    pub async fn delete_finished_exams(&self, before: NaiveDate) -> Result<u64, Error> {
+        let deleted = sqlx::query!(
+            "DELETE FROM exams WHERE done AND session_id IS NULL AND day < $1;",
+            before
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(deleted.rows_affected())
+    }
+
+    pub async fn get_all_sessions(&self) -> Result<Vec<DbSession>, Error> {
+        let sessions = sqlx::query!(
+            "SELECT * FROM sessions WHERE guild_id IN (SELECT guild_id FROM guilds WHERE left_at IS NULL)"
+        )
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(sessions
+            .into_iter()
+            .map(|session| DbSession {
+                session_id: session.session_id,
+                guild_id: GuildId(session.guild_id as u64),
+                name: session.name,
+                start_day: session.start_day,
+                end_day: session.end_day,
+                start_announced: session.start_announced,
+                end_announced: session.end_announced,
+            })
+            .collect())
+    }
+
+    pub async fn get_guild_sessions(&self, guild_id: GuildId) -> Result<Vec<DbSession>, Error> {
+        let sessions = sqlx::query!(
+            "SELECT * FROM sessions WHERE guild_id = $1 ORDER BY start_day",
+            guild_id.0 as i64
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sessions
+            .into_iter()
+            .map(|session| DbSession {
+                session_id: session.session_id,
+                guild_id: GuildId(session.guild_id as u64),
+                name: session.name,
+                start_day: session.start_day,
+                end_day: session.end_day,
+                start_announced: session.start_announced,
+                end_announced: session.end_announced,
+            })
+            .collect())
+    }
+
+    pub async fn get_session(&self, session_id: i64) -> Result<Option<DbSession>, Error> {
+        let session = sqlx::query!("SELECT * FROM sessions WHERE session_id = $1", session_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(session.map(|session| DbSession {
+            session_id: session.session_id,
+            guild_id: GuildId(session.guild_id as u64),
+            name: session.name,
+            start_day: session.start_day,
+            end_day: session.end_day,
+            start_announced: session.start_announced,
+            end_announced: session.end_announced,
+        }))
+    }
+
+    // Inserts a DbSession, ignoring the session_id, and adds the exams on its days to it
+    pub async fn insert_session(&self, session: DbSession) -> Result<i64, sqlx::Error> {
+        let ret = sqlx::query!(
+            "INSERT INTO sessions(guild_id, name, start_day, end_day) VALUES($1, $2, $3, $4) RETURNING session_id;",
+            session.guild_id.0 as i64,
+            session.name,
+            session.start_day,
+            session.end_day
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        sqlx::query!(
+            "UPDATE exams SET session_id = $1 WHERE guild_id = $2 AND day BETWEEN $3 AND $4;",
+            ret.session_id,
+            session.guild_id.0 as i64,
+            session.start_day,
+            session.end_day
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(ret.session_id)
+    }
+
+    // Exams that are done are only kept for their session, so they're deleted with it
+    pub async fn delete_session(&self, session_id: i64) -> Result<(), Error> {
+        sqlx::query!(
+            "DELETE FROM exams WHERE session_id = $1 AND done;",
+            session_id
+        )
+        .execute(&self.pool)
+        .await?;
+        sqlx::query!("DELETE FROM sessions WHERE session_id = $1;", session_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_session_start_announced(&self, session_id: i64) -> Result<(), Error> {
+        sqlx::query!(
+            "UPDATE sessions SET start_announced = TRUE WHERE session_id = $1;",
+            session_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_session_end_announced(&self, session_id: i64) -> Result<(), Error> {
+        sqlx::query!(
+            "UPDATE sessions SET end_announced = TRUE WHERE session_id = $1;",
+            session_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // Returns how many exams are done and how many there are in total
+    pub async fn count_session_exams(&self, session_id: i64) -> Result<(i64, i64), Error> {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) FILTER (WHERE done) AS done, COUNT(*) AS total FROM exams WHERE session_id = $1",
+            session_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((count.done.unwrap_or(0), count.total.unwrap_or(0)))
+    }
+
+    // Exams of a session the user takes, including those of the given roles
+    pub async fn get_user_session_exams(
+        &self,
+        session_id: i64,
+        user_id: UserId,
+        roles: &[RoleId],
+    ) -> Result<Vec<DbSessionExam>, Error> {
+        let roles: Vec<i64> = roles.iter().map(|role_id| role_id.0 as i64).collect();
+        let exams = sqlx::query!(
+            "SELECT day, exam_name, done FROM exams WHERE session_id = $1 AND (
+                (NOT group_exam AND role_id IS NULL AND user_id = $2)
+                OR exam_id IN (SELECT exam_id FROM exam_participants WHERE user_id = $2)
+                OR role_id = ANY($3)
+            ) ORDER BY day",
+            session_id,
+            user_id.0 as i64,
+            &roles
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(exams
+            .into_iter()
+            .map(|exam| DbSessionExam {
+                day: exam.day,
+                exam_name: exam.exam_name,
+                done: exam.done,
+            })
+            .collect())
+    }
+
+    // Returns the deleted exams
+    pub async fn delete_role_exams(
+        &self,
+        guild_id: GuildId,
+        role_id: RoleId,
+    ) -> Result<Vec<DbExam>, Error> {
+        let exams = sqlx::query!(
+            "DELETE FROM exams WHERE guild_id = $1 AND role_id = $2 RETURNING *;",
+            guild_id.0 as i64,
+            role_id.0 as i64
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(exams
+            .into_iter()
+            .map(|exam| DbExam {
+                exam_id: exam.exam_id,
+                user_id: UserId(exam.user_id as u64),
+                guild_id: GuildId(exam.guild_id as u64),
+                day: exam.day,
+                exam_name: exam.exam_name,
+                time: exam.exam_time,
+                message: exam.message,
+                reminded: exam.reminded,
+                group: exam.group_exam,
+                role: exam.role_id.map(|role_id| RoleId(role_id as u64)),
+            })
+            .collect())
+    }
 }
 
 impl TypeMapKey for Database {
@@ -0,0 +1,136 @@
+// Follow-up messages after an exam, asking how it went
+
+use std::str::FromStr;
+
+use poise::serenity_prelude::{
+    ButtonStyle, Context, CreateComponents, InteractionResponseType, MessageComponentInteraction,
+    ReactionType,
+};
+
+use crate::{
+    database::Database,
+    i18n::{tr, Language},
+    Error,
+};
+
+// Prefix of the custom id of the follow-up buttons, followed by the exam id and the result
+const BUTTON_PREFIX: &str = "followup";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FollowUpResult {
+    Good,
+    Okay,
+    Bad,
+}
+
+const RESULTS: [FollowUpResult; 3] = [
+    FollowUpResult::Good,
+    FollowUpResult::Okay,
+    FollowUpResult::Bad,
+];
+
+impl FollowUpResult {
+    // Name stored in the database
+    pub fn code(&self) -> &'static str {
+        match self {
+            FollowUpResult::Good => "good",
+            FollowUpResult::Okay => "okay",
+            FollowUpResult::Bad => "bad",
+        }
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            FollowUpResult::Good => "😀",
+            FollowUpResult::Okay => "😐",
+            FollowUpResult::Bad => "😩",
+        }
+    }
+}
+
+impl FromStr for FollowUpResult {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RESULTS
+            .into_iter()
+            .find(|result| result.code() == s)
+            .ok_or(())
+    }
+}
+
+// Follow-up message for guilds that didn't set a format
+pub fn default_followup_format(language: Language) -> &'static str {
+    match language {
+        Language::English => "{if name}How did {name} go?{else}How did your exam go?{end}",
+        Language::Dutch => "{if name}Hoe ging {name}?{else}Hoe ging je examen?{end}",
+    }
+}
+
+// One button per result
+pub fn create_buttons(components: &mut CreateComponents, exam_id: i64) -> &mut CreateComponents {
+    components.create_action_row(|row| {
+        for result in RESULTS {
+            row.create_button(|button| {
+                button
+                    .custom_id(format!("{}:{}:{}", BUTTON_PREFIX, exam_id, result.code()))
+                    .emoji(ReactionType::Unicode(result.emoji().to_owned()))
+                    .style(ButtonStyle::Secondary)
+            });
+        }
+        row
+    })
+}
+
+fn parse_custom_id(custom_id: &str) -> Option<(i64, FollowUpResult)> {
+    let mut parts = custom_id.split(':');
+    if parts.next()? != BUTTON_PREFIX {
+        return None;
+    }
+    let exam_id = parts.next()?.parse().ok()?;
+    let result = parts.next()?.parse().ok()?;
+    Some((exam_id, result))
+}
+
+// Saves the answer when someone clicks one of the buttons under a follow-up
+pub async fn handle_button(
+    ctx: &Context,
+    database: &Database,
+    component: &MessageComponentInteraction,
+) -> Result<(), Error> {
+    let (exam_id, result) = if let Some(button) = parse_custom_id(&component.data.custom_id) {
+        button
+    } else {
+        return Ok(());
+    };
+
+    let guild_language = match component.guild_id {
+        Some(guild_id) => database
+            .get_guild(guild_id)
+            .await?
+            .and_then(|guild| guild.language),
+        None => None,
+    };
+    let language = guild_language
+        .or_else(|| Language::from_locale(&component.locale))
+        .unwrap_or_default();
+
+    let content = if database
+        .set_exam_result(exam_id, component.user.id, result)
+        .await?
+    {
+        tr!(language, "followup-saved", result = result.emoji())
+    } else {
+        tr!(language, "followup-not-yours")
+    };
+
+    component
+        .create_interaction_response(ctx, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|data| data.content(content).ephemeral(true))
+        })
+        .await?;
+
+    Ok(())
+}
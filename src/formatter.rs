@@ -1,35 +1,674 @@
-use crate::database::DbExam;
+use std::fmt::Display;
+
+use chrono::{Datelike, NaiveDate};
+use log::warn;
 use once_cell::sync::Lazy;
+use poise::serenity_prelude::Mentionable;
+use rand::seq::SliceRandom;
 use regex::Regex;
 
-// $() => Only when exam name is empty
-// #() => Only when exam name is not empty
+use crate::{
+    database::{DbExam, DbMessage},
+    i18n::{tr, Language},
+};
+
+// Template language for the reminder message:
+// {name}                         => variable, see `Variable` for all of them
+// {if name}...{else}...{end}     => only when the variable isn't empty, {else} is optional
+// {if !name}...{end}             => only when the variable is empty
+// {{ and }}                      => literal braces
+// \n                             => newline, \\ is a literal backslash
+
+pub const DEFAULT_FORMAT: &str =
+    "{if name}Good luck with {name}!{else}Good luck with your exam!{end}";
+
+// Reminder message for guilds that didn't set a format
+pub fn default_format(language: Language) -> &'static str {
+    match language {
+        Language::English => DEFAULT_FORMAT,
+        Language::Dutch => "{if name}Succes met {name}!{else}Succes met je examen!{end}",
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Variable {
+    User,
+    Name,
+    Date,
+    Weekday,
+    DaysUntil,
+    Time,
+    Guild,
+}
+
+pub const VARIABLES: [(&str, Variable); 7] = [
+    ("user", Variable::User),
+    ("name", Variable::Name),
+    ("date", Variable::Date),
+    ("weekday", Variable::Weekday),
+    ("days_until", Variable::DaysUntil),
+    ("time", Variable::Time),
+    ("guild", Variable::Guild),
+];
+
+// Discord's message limit
+pub const DISCORD_MESSAGE_LENGTH: usize = 2000;
+
+// Longest {user}, the scheduler sends mentions that don't fit in separate messages
+pub const MAX_MENTIONS_LENGTH: usize = 500;
+
+// Discord's message limit, minus the mentions in front of the reminder
+const MAX_MESSAGE_LENGTH: usize = DISCORD_MESSAGE_LENGTH - MAX_MENTIONS_LENGTH - "\n".len();
 
-// $name => exam name (can only be used inside #())
+// The longest value a variable can have, in characters.
+// Exam and guild names are assumed to be at most 100 characters.
+fn max_length(variable: Variable) -> usize {
+    match variable {
+        Variable::User => MAX_MENTIONS_LENGTH,
+        Variable::Name => 100,
+        Variable::Date => "2024-01-01".len(),
+        Variable::Weekday => "Wednesday".len(),
+        Variable::DaysUntil => 3,
+        Variable::Time => "12:00".len(),
+        Variable::Guild => 100,
+    }
+}
+
+// $(...), #(...) and $name from before the template language
+static OLD_SYNTAX_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"[$#]\(|\$name").unwrap());
+
+// The values of the variables for one exam
+pub struct Variables {
+    pub user: String,
+    pub name: String,
+    pub date: String,
+    pub weekday: String,
+    pub days_until: String,
+    pub time: String,
+    pub guild: String,
+    language: Language,
+}
+
+impl Variables {
+    pub fn new(exam: &DbExam, today: NaiveDate, guild: &str, language: Language) -> Self {
+        let weekday = match language {
+            Language::English => exam.day.format("%A").to_string(),
+            _ => language
+                .locale()
+                .weekday_name(exam.day.weekday())
+                .to_owned(),
+        };
+        Variables {
+            user: exam.user_id.mention().to_string(),
+            name: exam.exam_name.clone(),
+            date: exam.day.format("%Y-%m-%d").to_string(),
+            weekday,
+            days_until: (exam.day - today).num_days().to_string(),
+            time: exam
+                .time
+                .map(|time| time.format("%H:%M").to_string())
+                .unwrap_or_default(),
+            guild: guild.to_owned(),
+            language,
+        }
+    }
 
-// Not even gonna bother with escapes lol
+    fn get(&self, variable: Variable) -> &str {
+        match variable {
+            Variable::User => &self.user,
+            Variable::Name => &self.name,
+            Variable::Date => &self.date,
+            Variable::Weekday => &self.weekday,
+            Variable::DaysUntil => &self.days_until,
+            Variable::Time => &self.time,
+            Variable::Guild => &self.guild,
+        }
+    }
+}
 
-pub const DEFAULT_FORMAT: &'static str = "$(Good luck with your exam!)#(Good luck with $name!)";
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Node {
+    Text(String),
+    Variable(Variable),
+    If {
+        variable: Variable,
+        negated: bool,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+// Byte range `start..end` of the template the error is about
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TemplateError {
+    pub message: String,
+    pub template: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let column = self.template[..self.start].chars().count();
+        // Only show the part of long templates around the error
+        let context_start = column.saturating_sub(40);
+        let excerpt: String = self.template.chars().skip(context_start).take(80).collect();
+        let width = self.template[self.start..self.end]
+            .chars()
+            .count()
+            .clamp(1, 80 - (column - context_start));
+        write!(
+            f,
+            "{} (at position {})\n```\n{}\n{}{}\n```",
+            self.message,
+            column + 1,
+            excerpt,
+            " ".repeat(column - context_start),
+            "^".repeat(width)
+        )
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+// A {...} tag, without its braces
+enum Tag {
+    Variable(Variable),
+    If(Variable, bool),
+    Else,
+    End,
+}
+
+// An {if} that hasn't been closed yet
+struct OpenIf {
+    variable: Variable,
+    negated: bool,
+    start: usize,
+    then: Option<Vec<Node>>, // Set once {else} is reached
+}
+
+struct Parser<'t> {
+    template: &'t str,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: impl Into<String>, start: usize, end: usize) -> TemplateError {
+        TemplateError {
+            message: message.into(),
+            template: self.template.to_owned(),
+            start,
+            end,
+        }
+    }
+
+    fn variable(&self, name: &str, start: usize) -> Result<Variable, TemplateError> {
+        VARIABLES
+            .iter()
+            .find(|(variable_name, _)| *variable_name == name)
+            .map(|(_, variable)| *variable)
+            .ok_or_else(|| {
+                self.error(
+                    format!(
+                        "Unknown variable \"{}\", use one of {}",
+                        name,
+                        VARIABLES
+                            .iter()
+                            .map(|(name, _)| format!("{{{}}}", name))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    start,
+                    start + name.len(),
+                )
+            })
+    }
+
+    // `content` is the text between the braces, starting at `start`
+    fn tag(&self, content: &str, start: usize) -> Result<Tag, TemplateError> {
+        let trimmed = content.trim();
+        let start = start + content.len() - content.trim_start().len();
+        match trimmed {
+            "else" => Ok(Tag::Else),
+            "end" => Ok(Tag::End),
+            "" => Err(self.error(
+                "Empty {}, use {{ and }} for literal braces",
+                start - 1,
+                start + 1,
+            )),
+            _ => {
+                if let Some(condition) = trimmed.strip_prefix("if ") {
+                    let condition_start =
+                        start + 3 + condition.len() - condition.trim_start().len();
+                    let condition = condition.trim();
+                    match condition.strip_prefix('!') {
+                        Some(name) => Ok(Tag::If(
+                            self.variable(name.trim(), condition_start + 1)?,
+                            true,
+                        )),
+                        None => Ok(Tag::If(self.variable(condition, condition_start)?, false)),
+                    }
+                } else {
+                    Ok(Tag::Variable(self.variable(trimmed, start)?))
+                }
+            }
+        }
+    }
+
+    fn parse(&self) -> Result<Template, TemplateError> {
+        let mut stack: Vec<(OpenIf, Vec<Node>)> = Vec::new();
+        let mut nodes = Vec::new();
+        let mut text = String::new();
+
+        let mut chars = self.template.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '{' if chars.peek().map(|(_, c)| *c) == Some('{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek().map(|(_, c)| *c) == Some('}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '}' => return Err(self.error("Unmatched }, use }} for a literal }", i, i + 1)),
+                '\\' => match chars.peek().map(|(_, c)| *c) {
+                    Some('n') => {
+                        chars.next();
+                        text.push('\n');
+                    }
+                    Some('\\') => {
+                        chars.next();
+                        text.push('\\');
+                    }
+                    _ => text.push('\\'),
+                },
+                '{' => {
+                    let end = match self.template[i + 1..].find(['}', '{']) {
+                        Some(offset) if self.template[i + 1 + offset..].starts_with('}') => {
+                            i + 1 + offset
+                        }
+                        _ => {
+                            return Err(self.error("Unclosed {, use {{ for a literal {", i, i + 1))
+                        }
+                    };
+                    // Skip to the closing brace
+                    while chars.next_if(|(j, _)| *j < end).is_some() {}
+                    chars.next();
+
+                    if !text.is_empty() {
+                        nodes.push(Node::Text(std::mem::take(&mut text)));
+                    }
+                    match self.tag(&self.template[i + 1..end], i + 1)? {
+                        Tag::Variable(variable) => nodes.push(Node::Variable(variable)),
+                        Tag::If(variable, negated) => {
+                            let open = OpenIf {
+                                variable,
+                                negated,
+                                start: i,
+                                then: None,
+                            };
+                            stack.push((open, std::mem::take(&mut nodes)));
+                        }
+                        Tag::Else => match stack.last_mut() {
+                            Some((open, _)) if open.then.is_none() => {
+                                open.then = Some(std::mem::take(&mut nodes));
+                            }
+                            Some(_) => {
+                                return Err(self.error(
+                                    "This {if} already has an {else}",
+                                    i,
+                                    end + 1,
+                                ))
+                            }
+                            None => return Err(self.error("{else} without {if}", i, end + 1)),
+                        },
+                        Tag::End => match stack.pop() {
+                            Some((open, outer)) => {
+                                let (then, otherwise) = match open.then {
+                                    Some(then) => (then, std::mem::replace(&mut nodes, outer)),
+                                    None => (std::mem::replace(&mut nodes, outer), Vec::new()),
+                                };
+                                nodes.push(Node::If {
+                                    variable: open.variable,
+                                    negated: open.negated,
+                                    then,
+                                    otherwise,
+                                });
+                            }
+                            None => return Err(self.error("{end} without {if}", i, end + 1)),
+                        },
+                    }
+                }
+                c => text.push(c),
+            }
+        }
+
+        if let Some((open, _)) = stack.last() {
+            let end = self.template[open.start..]
+                .find('}')
+                .map_or(self.template.len(), |offset| open.start + offset + 1);
+            return Err(self.error("{if} without {end}", open.start, end));
+        }
+        if !text.is_empty() {
+            nodes.push(Node::Text(text));
+        }
+        Ok(Template { nodes })
+    }
+}
+
+fn max_rendered_length(nodes: &[Node]) -> usize {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Text(text) => text.chars().count(),
+            Node::Variable(variable) => max_length(*variable),
+            Node::If {
+                then, otherwise, ..
+            } => max_rendered_length(then).max(max_rendered_length(otherwise)),
+        })
+        .sum()
+}
+
+fn render_nodes(nodes: &[Node], variables: &Variables, output: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Variable(variable) => output.push_str(variables.get(*variable)),
+            Node::If {
+                variable,
+                negated,
+                then,
+                otherwise,
+            } => {
+                if variables.get(*variable).is_empty() == *negated {
+                    render_nodes(then, variables, output);
+                } else {
+                    render_nodes(otherwise, variables, output);
+                }
+            }
+        }
+    }
+}
 
-static NO_NAME_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$\(([^)]*)\)").unwrap());
-static NAME_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\#\(([^)]*)\)").unwrap());
-static NAME_REPLACE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$name").unwrap());
-static NEWLINE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\\n").unwrap());
+impl Template {
+    pub fn parse(template: &str) -> Result<Template, TemplateError> {
+        Parser { template }.parse()
+    }
 
-pub fn format_exam<S: AsRef<str>>(format: S, exam: DbExam) -> String {
-    let filtered_format = if exam.exam_name.is_empty() {
-        let f = NO_NAME_REGEX.replace_all(format.as_ref(), "$1");
-        NAME_REGEX.replace_all(f.as_ref(), "").to_string()
+    pub fn render(&self, variables: &Variables) -> String {
+        let mut output = String::new();
+        render_nodes(&self.nodes, variables, &mut output);
+        output
+    }
+
+    // Parses a format an admin wants to save, with extra checks for mistakes that would still parse
+    pub fn validate(template: &str) -> Result<Template, TemplateError> {
+        if let Some(old) = OLD_SYNTAX_REGEX.find(template) {
+            return Err(TemplateError {
+                message: "This is the old format syntax, use {name} for the exam name and {if name}...{else}...{end} for text that depends on it".to_owned(),
+                template: template.to_owned(),
+                start: old.start(),
+                end: old.end(),
+            });
+        }
+
+        let parsed = Template::parse(template)?;
+
+        let length = max_rendered_length(&parsed.nodes);
+        if length > MAX_MESSAGE_LENGTH {
+            return Err(TemplateError {
+                message: format!(
+                    "This message can be up to {} characters long, Discord only allows {}",
+                    length, MAX_MESSAGE_LENGTH
+                ),
+                template: template.to_owned(),
+                start: 0,
+                end: template.len(),
+            });
+        }
+
+        Ok(parsed)
+    }
+}
+
+// How reminders are sent
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum Delivery {
+    #[default]
+    #[name = "text"]
+    #[name_localized("nl", "tekst")]
+    Text,
+    #[name = "embed"]
+    Embed,
+}
+
+impl Delivery {
+    // Name stored in the database
+    pub fn code(&self) -> &'static str {
+        match self {
+            Delivery::Text => "text",
+            Delivery::Embed => "embed",
+        }
+    }
+}
+
+// Title of reminder embeds when the guild didn't set one
+pub fn default_embed_title(language: Language) -> &'static str {
+    match language {
+        Language::English => "{if name}{name}{else}Your exam{end} on {weekday}",
+        Language::Dutch => "{if name}{name}{else}Je examen{end} op {weekday}",
+    }
+}
+
+// Which exams a message from the guild's message pool can be used for
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum MessagePool {
+    #[default]
+    #[name = "all exams"]
+    #[name = "all"]
+    #[name_localized("nl", "alle examens")]
+    All,
+    #[name = "exams with a name"]
+    #[name = "named"]
+    #[name_localized("nl", "examens met een naam")]
+    Named,
+    #[name = "exams without a name"]
+    #[name = "unnamed"]
+    #[name_localized("nl", "examens zonder naam")]
+    Unnamed,
+}
+
+impl MessagePool {
+    // Name stored in the database
+    pub fn code(&self) -> &'static str {
+        match self {
+            MessagePool::All => "all",
+            MessagePool::Named => "named",
+            MessagePool::Unnamed => "unnamed",
+        }
+    }
+
+    fn contains(&self, exam: &DbExam) -> bool {
+        match self {
+            MessagePool::All => true,
+            MessagePool::Named => !exam.exam_name.is_empty(),
+            MessagePool::Unnamed => exam.exam_name.is_empty(),
+        }
+    }
+}
+
+// Picks a random message for the exam, weighted, and if possible not the one the user got last time.
+// None if the guild has no messages for this exam, the guild's format is used then.
+pub fn choose_message<'m>(
+    messages: &'m [DbMessage],
+    exam: &DbExam,
+    last_message_id: Option<i64>,
+) -> Option<&'m DbMessage> {
+    let candidates: Vec<_> = messages
+        .iter()
+        .filter(|message| message.pool.contains(exam))
+        .collect();
+    let fresh: Vec<_> = candidates
+        .iter()
+        .copied()
+        .filter(|message| Some(message.message_id) != last_message_id)
+        .collect();
+    let candidates = if fresh.is_empty() { candidates } else { fresh };
+
+    candidates
+        .choose_weighted(&mut rand::thread_rng(), |message| message.weight)
+        .ok()
+        .copied()
+}
+
+// Formats can only be saved when they parse, but fall back to the default just in case
+pub fn format_exam(format: &str, variables: &Variables) -> String {
+    let template = Template::parse(format).unwrap_or_else(|err| {
+        warn!("Invalid format \"{}\": {}", format, err.message);
+        Template::parse(default_format(variables.language)).unwrap()
+    });
+    template.render(variables)
+}
+
+// Who takes the exam, as mentions so they're shown as names
+pub fn mention_owner(exam: &DbExam, language: Language) -> String {
+    if let Some(role_id) = exam.role {
+        role_id.mention().to_string()
+    } else if exam.group {
+        tr!(language, "group-exam")
     } else {
-        let f = NO_NAME_REGEX.replace_all(format.as_ref(), "");
-        let f = NAME_REGEX.replace_all(f.as_ref(), "$1");
-        NAME_REPLACE_REGEX
-            .replace_all(&f, exam.exam_name)
-            .to_string()
-    };
-    let filtered_format = NEWLINE
-        .replace_all(filtered_format.as_ref(), "\n")
-        .to_string();
-
-    filtered_format
+        exam.user_id.mention().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables(name: &str) -> Variables {
+        Variables {
+            user: "<@1>".to_owned(),
+            name: name.to_owned(),
+            date: "2025-01-13".to_owned(),
+            weekday: "Monday".to_owned(),
+            days_until: "1".to_owned(),
+            time: String::new(),
+            guild: "Guild".to_owned(),
+            language: Language::English,
+        }
+    }
+
+    fn render(template: &str, name: &str) -> String {
+        Template::parse(template).unwrap().render(&variables(name))
+    }
+
+    fn error(template: &str) -> TemplateError {
+        Template::validate(template).unwrap_err()
+    }
+
+    #[test]
+    fn default_format_with_and_without_name() {
+        assert_eq!(render(DEFAULT_FORMAT, "Algebra"), "Good luck with Algebra!");
+        assert_eq!(render(DEFAULT_FORMAT, ""), "Good luck with your exam!");
+        assert_eq!(
+            render(default_format(Language::Dutch), "Algebra"),
+            "Succes met Algebra!"
+        );
+    }
+
+    #[test]
+    fn negated_if() {
+        let template = "{if !name}No name{end}{if name}{name}{end}";
+        assert_eq!(render(template, "Algebra"), "Algebra");
+        assert_eq!(render(template, ""), "No name");
+    }
+
+    #[test]
+    fn nested_blocks() {
+        let template = "{if name}{name}{if !time} (all day){else} at {time}{end}{else}Exam{end}";
+        assert_eq!(render(template, "Algebra"), "Algebra (all day)");
+        assert_eq!(render(template, ""), "Exam");
+
+        let mut variables = variables("Algebra");
+        variables.time = "09:00".to_owned();
+        assert_eq!(
+            Template::parse(template).unwrap().render(&variables),
+            "Algebra at 09:00"
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(render("{{name}} is {name}", "Algebra"), "{name} is Algebra");
+        assert_eq!(render(r"Good\nluck \\n", ""), "Good\nluck \\n");
+        assert_eq!(render(r"C:\temp", ""), r"C:\temp");
+    }
+
+    #[test]
+    fn unclosed_blocks() {
+        let err = error("Hi {if name}{name}");
+        assert_eq!(err.message, "{if} without {end}");
+        assert_eq!((err.start, err.end), (3, 12));
+
+        let err = error("{if name}{if !time}x{end}");
+        assert_eq!(err.message, "{if} without {end}");
+        assert_eq!((err.start, err.end), (0, 9));
+
+        let err = error("Hi {name");
+        assert_eq!((err.start, err.end), (3, 4));
+        assert!(err.message.starts_with("Unclosed {"));
+
+        let err = error("Hi name}");
+        assert_eq!((err.start, err.end), (7, 8));
+
+        assert_eq!(error("x{end}").message, "{end} without {if}");
+        assert_eq!(error("x{else}").message, "{else} without {if}");
+        assert_eq!(
+            error("{if name}a{else}b{else}c{end}").message,
+            "This {if} already has an {else}"
+        );
+    }
+
+    #[test]
+    fn unknown_variable_position() {
+        let err = error("Good luck {nam}!");
+        assert!(err.message.starts_with("Unknown variable \"nam\""));
+        assert_eq!((err.start, err.end), (11, 14));
+        assert!(err.to_string().contains("(at position 12)"));
+        assert!(err.to_string().contains("\n           ^^^\n"));
+
+        let err = error("{if  !nme}x{end}");
+        assert!(err.message.starts_with("Unknown variable \"nme\""));
+        assert_eq!((err.start, err.end), (6, 9));
+    }
+
+    #[test]
+    fn old_syntax() {
+        let err = error("$(Good luck!)#(Good luck with $name!)");
+        assert!(err.message.starts_with("This is the old format syntax"));
+        assert_eq!((err.start, err.end), (0, 2));
+    }
+
+    #[test]
+    fn length_limit() {
+        assert!(Template::validate(&"x".repeat(MAX_MESSAGE_LENGTH)).is_ok());
+        assert!(Template::validate(&"x".repeat(MAX_MESSAGE_LENGTH + 1)).is_err());
+
+        // Variables count with their longest value, the longest branch of an {if} counts
+        let text = "x".repeat(MAX_MESSAGE_LENGTH - max_length(Variable::Name));
+        assert!(Template::validate(&format!("{}{{name}}", text)).is_ok());
+        assert!(Template::validate(&format!("{}{{name}}!", text)).is_err());
+        assert!(
+            Template::validate(&format!("{}{{if name}}{{name}}{{else}}short{{end}}", text)).is_ok()
+        );
+        assert!(
+            Template::validate(&format!("{}{{if name}}{{name}}{{else}}short{{end}}!", text))
+                .is_err()
+        );
+    }
 }
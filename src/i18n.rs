@@ -0,0 +1,651 @@
+use log::warn;
+use poise::{serenity_prelude::Cache, Context};
+
+use crate::{
+    database::DbGuild,
+    locale::{self, Locale},
+    Data, Error,
+};
+
+// Languages the bot replies and sends reminders in
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum Language {
+    #[default]
+    #[name = "English"]
+    #[name = "en"]
+    English,
+    #[name = "Nederlands"]
+    #[name = "nl"]
+    Dutch,
+}
+
+impl Language {
+    // Name stored in the database, same as the Discord locale and the parser's locale code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Dutch => "nl",
+        }
+    }
+
+    // Discord locales are either a language ("nl") or a language and a region ("en-US")
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        match locale.split('-').next()? {
+            "en" => Some(Language::English),
+            "nl" => Some(Language::Dutch),
+            _ => None,
+        }
+    }
+
+    // Month and weekday names
+    pub fn locale(&self) -> &'static Locale {
+        locale::find_locale(self.code()).unwrap()
+    }
+}
+
+// The guild's language, or the Discord language of whoever used the command
+pub async fn language(ctx: Context<'_, Data, Error>) -> Result<Language, Error> {
+    let guild_language = match ctx.guild_id() {
+        Some(guild_id) => ctx
+            .data()
+            .database
+            .get_guild(guild_id)
+            .await?
+            .and_then(|guild| guild.language),
+        None => None,
+    };
+
+    Ok(guild_language
+        .or_else(|| ctx.locale().and_then(Language::from_locale))
+        .unwrap_or_default())
+}
+
+// Reminders aren't sent in response to anyone, so they use the guild's Discord language instead
+pub fn guild_language(guild: &DbGuild, cache: &Cache) -> Language {
+    guild
+        .language
+        .or_else(|| {
+            guild
+                .guild_id
+                .to_guild_cached(cache)
+                .and_then(|cached| Language::from_locale(&cached.preferred_locale))
+        })
+        .unwrap_or_default()
+}
+
+// Translated text, with {name} placeholders filled in by the named arguments
+macro_rules! tr {
+    ($language:expr, $key:literal) => {
+        $crate::i18n::translate($language, $key).to_owned()
+    };
+    ($language:expr, $key:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            $crate::i18n::translate($language, $key),
+            &[$((stringify!($name), $value.to_string())),+],
+        )
+    };
+}
+pub(crate) use tr;
+
+pub fn translate(language: Language, key: &'static str) -> &'static str {
+    match TRANSLATIONS.iter().find(|(name, ..)| *name == key) {
+        Some((_, english, dutch)) => match language {
+            Language::English => english,
+            Language::Dutch => dutch,
+        },
+        None => {
+            warn!("Missing translation: {}", key);
+            key
+        }
+    }
+}
+
+// Placeholders without a value are kept, so user input like a message format passes through unchanged
+pub fn fill(text: &str, values: &[(&str, String)]) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest.find('}').and_then(|end| {
+            values
+                .iter()
+                .find(|(name, _)| *name == &rest[1..end])
+                .map(|(_, value)| (end, value))
+        });
+        match value {
+            Some((end, value)) => {
+                result.push_str(value);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+// (key, English, Dutch)
+// The Dutch texts use the Dutch command names from the name_localized attributes
+static TRANSLATIONS: &[(&str, &str, &str)] = &[
+    // /exam
+    ("invalid-date", "Invalid date: {date}", "Ongeldige datum: {date}"),
+    ("invalid-time", "Invalid time: {time}", "Ongeldige tijd: {time}"),
+    (
+        "exam-added",
+        "Added new exam for user {user} on {day}",
+        "Nieuw examen toegevoegd voor {user} op {day}",
+    ),
+    (
+        "exam-added-named",
+        "Added new exam for user {user} on {day}: \"{name}\"",
+        "Nieuw examen toegevoegd voor {user} op {day}: \"{name}\"",
+    ),
+    ("exam-deleted", "Deleted exam {exam}", "Examen {exam} verwijderd"),
+    ("exam-edited", "Updated exam {exam}", "Examen {exam} aangepast"),
+    (
+        "exam-duplicate",
+        "This user already has an exam with that name on that day",
+        "Deze gebruiker heeft al een examen met die naam op die dag",
+    ),
+    (
+        "exam-message-invalid",
+        "Could not set the message of this exam: {error}",
+        "Kon het bericht van dit examen niet instellen: {error}",
+    ),
+    (
+        "exam-not-found",
+        "No exam with id {id} exists",
+        "Er bestaat geen examen met id {id}",
+    ),
+    ("group-exam", "Group exam", "Groepsexamen"),
+    (
+        "group-exam-count",
+        "Group exam ({count} participants)",
+        "Groepsexamen ({count} deelnemers)",
+    ),
+    (
+        "group-created",
+        "Added group exam {exam} (ID: {id}). Members can join it with `/exam join`.",
+        "Groepsexamen {exam} toegevoegd (ID: {id}). Leden kunnen meedoen met `/examen meedoen`.",
+    ),
+    (
+        "role-exam-created",
+        "Added {exam} (ID: {id}) for everyone with the role",
+        "{exam} (ID: {id}) toegevoegd voor iedereen met de rol",
+    ),
+    (
+        "role-exams-deleted",
+        "The role {role} was deleted, so its exams were removed:",
+        "De rol {role} is verwijderd, dus de examens ervan zijn ook verwijderd:",
+    ),
+    (
+        "not-group-exam",
+        "Exam {id} is not a group exam",
+        "Examen {id} is geen groepsexamen",
+    ),
+    (
+        "participant-admin-only",
+        "Only administrators can add or remove someone else",
+        "Alleen beheerders kunnen iemand anders toevoegen of verwijderen",
+    ),
+    (
+        "participant-joined",
+        "{user} now takes {exam}",
+        "{user} doet nu mee aan {exam}",
+    ),
+    (
+        "participant-already-joined",
+        "{user} already takes {exam}",
+        "{user} doet al mee aan {exam}",
+    ),
+    (
+        "participant-left",
+        "{user} no longer takes {exam}",
+        "{user} doet niet meer mee aan {exam}",
+    ),
+    (
+        "participant-not-joined",
+        "{user} doesn't take {exam}",
+        "{user} doet niet mee aan {exam}",
+    ),
+    // /exams
+    ("exams-guild", "Exams in {guild}:", "Examens in {guild}:"),
+    (
+        "exams-user",
+        "Exams for {user} in {guild}:",
+        "Examens van {user} in {guild}:",
+    ),
+    // /messages
+    (
+        "message-invalid",
+        "Could not add the message: {error}",
+        "Kon het bericht niet toevoegen: {error}",
+    ),
+    (
+        "message-added",
+        "Added message {id} for {pool}. Reminders now pick a random message instead of the one from `/settings message`.",
+        "Bericht {id} toegevoegd voor {pool}. Herinneringen kiezen nu een willekeurig bericht in plaats van het bericht uit `/instellingen bericht`.",
+    ),
+    ("message-removed", "Removed message {id}", "Bericht {id} verwijderd"),
+    (
+        "message-not-found",
+        "No message with id {id} exists",
+        "Er bestaat geen bericht met id {id}",
+    ),
+    (
+        "messages-empty",
+        "This server has no random messages, reminders use the message from `/settings message`. Use `/messages add` to add some!",
+        "Deze server heeft geen willekeurige berichten, herinneringen gebruiken het bericht uit `/instellingen bericht`. Gebruik `/berichten toevoegen` om er een paar toe te voegen!",
+    ),
+    (
+        "messages-header",
+        "Random reminder messages:",
+        "Willekeurige herinneringsberichten:",
+    ),
+    (
+        "messages-entry",
+        "{id}: `{format}` ({pool}, weight {weight})",
+        "{id}: `{format}` ({pool}, gewicht {weight})",
+    ),
+    // Schedule parsing
+    (
+        "parse-header",
+        "Parsed exams for user {user}",
+        "Ingelezen examens voor {user}",
+    ),
+    (
+        "parse-help",
+        "use `/parse accept` to add these exams to the bot\nuse `/parse reject` to reject these exams and stop the parsing interaction\nuse `/parse remove <id>` to remove one of these exams from the parsed exams (for example, if it was parsed incorrectly)\nuse `/parse user <user> [id]` to change who takes these exams (or only one of them)\nuse `/parse message <id> <message>` to give one of these exams its own reminder",
+        "gebruik `/inlezen accepteren` om deze examens aan de bot toe te voegen\ngebruik `/inlezen afwijzen` om deze examens af te wijzen en het inlezen te stoppen\ngebruik `/inlezen verwijderen <id>` om een van deze examens uit de ingelezen examens te verwijderen (bijvoorbeeld als het verkeerd is ingelezen)\ngebruik `/inlezen gebruiker <gebruiker> [id]` om te veranderen wie deze examens aflegt (of maar één ervan)\ngebruik `/inlezen bericht <id> <bericht>` om een van deze examens een eigen herinnering te geven",
+    ),
+    (
+        "parse-update-hint",
+        "use `/parse accept update:True` to move exams that were already added on another day to their new date, instead of adding them again",
+        "gebruik `/inlezen accepteren verplaatsen:True` om examens die al op een andere dag zijn toegevoegd naar hun nieuwe datum te verplaatsen, in plaats van ze opnieuw toe te voegen",
+    ),
+    (
+        "parse-expand-hint",
+        "exams spanning several days become one exam per day when accepted, use `/parse expand <id>` to do this first and remove days",
+        "examens over meerdere dagen worden bij het accepteren één examen per dag, gebruik `/inlezen uitsplitsen <id>` om dit vooraf te doen en dagen te verwijderen",
+    ),
+    ("parse-for", " (for {user})", " (voor {user})"),
+    ("conflict-duplicate", " ⚠️ already added", " ⚠️ al toegevoegd"),
+    (
+        "conflict-rescheduled",
+        " ⚠️ already added on {day}",
+        " ⚠️ al toegevoegd op {day}",
+    ),
+    (
+        "conflict-same-day",
+        " ⚠️ another exam on the same day",
+        " ⚠️ nog een examen op dezelfde dag",
+    ),
+    (
+        "conflict-same-day-named",
+        " ⚠️ same day as {name}",
+        " ⚠️ zelfde dag als {name}",
+    ),
+    (
+        "parse-empty",
+        "No exams found in this message.",
+        "Geen examens gevonden in dit bericht.",
+    ),
+    ("source-message", "message", "bericht"),
+    ("source-embed", "embed {number}", "embed {number}"),
+    (
+        "attachment-too-large",
+        "{file} was not parsed because it is too large.",
+        "{file} is niet ingelezen omdat het te groot is.",
+    ),
+    (
+        "warnings-hidden",
+        "More warnings were hidden as to not exceed the maximum message length",
+        "Meer waarschuwingen zijn verborgen om de maximale berichtlengte niet te overschrijden",
+    ),
+    ("parse-inserted", "Inserted {count} exams.", "{count} examens toegevoegd."),
+    (
+        "parse-expanded",
+        " Split {count} exams spanning several days into one exam per day.",
+        " {count} examens over meerdere dagen opgesplitst in één examen per dag.",
+    ),
+    (
+        "parse-moved",
+        " Moved {count} exams to their new date.",
+        " {count} examens naar hun nieuwe datum verplaatst.",
+    ),
+    (
+        "parse-duplicates",
+        " {count} duplicates already in the bot.",
+        " {count} dubbele examens stonden al in de bot.",
+    ),
+    (
+        "parse-no-interaction",
+        "You don't have an ongoing 'parse' interaction. Use the context menu to start one first! (right click > Apps > Parse message and add exams)",
+        "Je bent geen examens aan het inlezen. Gebruik eerst het contextmenu om te beginnen! (rechtsklik > Apps > Examens inlezen uit bericht)",
+    ),
+    (
+        "parse-rejected",
+        "Rejected parse interaction.",
+        "Ingelezen examens afgewezen.",
+    ),
+    ("invalid-index", "Invalid index", "Ongeldige index"),
+    (
+        "parse-single-day",
+        "This exam only spans one day",
+        "Dit examen duurt maar één dag",
+    ),
+    // /settings
+    (
+        "no-send-permission",
+        "This bot doesn't have permissions to send messages in {channel}",
+        "Deze bot mag geen berichten sturen in {channel}",
+    ),
+    (
+        "channel-updated",
+        "Updated bot message channel to {channel}!",
+        "Berichtkanaal van de bot aangepast naar {channel}!",
+    ),
+    (
+        "time-updated",
+        "Updated bot message time to {time} {timezone}!",
+        "Berichttijd van de bot aangepast naar {time} {timezone}!",
+    ),
+    ("invalid-timezone", "Invalid timezone", "Ongeldige tijdzone"),
+    ("invalid-time-format", "Invalid time format", "Ongeldig tijdformaat"),
+    (
+        "invalid-time-format-timezone",
+        "Invalid time format and timezone",
+        "Ongeldig tijdformaat en ongeldige tijdzone",
+    ),
+    (
+        "format-invalid",
+        "Could not update the bot format: {error}\nThe previous format is kept: `{format}`",
+        "Kon het berichtformaat niet aanpassen: {error}\nHet vorige formaat blijft behouden: `{format}`",
+    ),
+    (
+        "format-updated",
+        "Updated bot format to \"{format}\"!\n\nExample for nameless exam:\n{nameless}\n\nExample for named exam:\n{named}",
+        "Berichtformaat aangepast naar \"{format}\"!\n\nVoorbeeld voor een examen zonder naam:\n{nameless}\n\nVoorbeeld voor een examen met naam:\n{named}",
+    ),
+    (
+        "example-exam-name",
+        "Algorithms and Datastructures",
+        "Algoritmen en Datastructuren",
+    ),
+    (
+        "locales-empty",
+        "Specify at least one language",
+        "Geef minstens één taal op",
+    ),
+    (
+        "locales-unknown",
+        "Unknown language(s): {unknown}\nAvailable languages: {available}",
+        "Onbekende taal/talen: {unknown}\nBeschikbare talen: {available}",
+    ),
+    (
+        "locales-updated",
+        "Updated schedule parsing languages to {locales}!",
+        "Talen voor het inlezen van roosters aangepast naar {locales}!",
+    ),
+    (
+        "date-order-updated",
+        "Numeric dates are now read as {order}!",
+        "Numerieke datums worden nu gelezen als {order}!",
+    ),
+    (
+        "delivery-updated",
+        "Reminders are now sent as {delivery}!",
+        "Herinneringen worden nu verstuurd als {delivery}!",
+    ),
+    (
+        "embed-title-invalid",
+        "Could not update the embed title: {error}",
+        "Kon de titel van de embed niet aanpassen: {error}",
+    ),
+    ("invalid-colour", "Invalid colour: {colour}", "Ongeldige kleur: {colour}"),
+    ("invalid-url", "Invalid URL: {url}", "Ongeldige URL: {url}"),
+    (
+        "embed-updated",
+        "Updated the reminder embed!",
+        "Embed van de herinneringen aangepast!",
+    ),
+    (
+        "embed-updated-hint",
+        "Updated the reminder embed! Use `/settings delivery embed` to send reminders as embeds.",
+        "Embed van de herinneringen aangepast! Gebruik `/instellingen verzending embed` om herinneringen als embed te versturen.",
+    ),
+    (
+        "language-updated",
+        "The bot now replies in English!",
+        "De bot antwoordt nu in het Nederlands!",
+    ),
+    (
+        "language-reset",
+        "The bot now replies in the Discord language of whoever uses a command.",
+        "De bot antwoordt nu in de Discord-taal van wie een commando gebruikt.",
+    ),
+    (
+        "followup-invalid",
+        "Could not update the follow-up message: {error}",
+        "Kon het vervolgbericht niet aanpassen: {error}",
+    ),
+    (
+        "followup-updated",
+        "Follow-ups are now sent at {time} on the day of each exam!",
+        "Vervolgberichten worden nu om {time} op de dag van elk examen verstuurd!",
+    ),
+    (
+        "followup-disabled",
+        "Follow-ups are turned off.",
+        "Vervolgberichten staan uit.",
+    ),
+    (
+        "settings-none",
+        "No settings saved for this guild.",
+        "Er zijn geen instellingen opgeslagen voor deze server.",
+    ),
+    (
+        "settings-list",
+        "**Settings**:\nChannel: {channel}\nTime: {time} {timezone}\nFormat: {format}\nParsing languages: {locales}\nDate order: {order}\nDelivery: {delivery}\nLanguage: {language}\nFollow-ups: {followup}\nLive countdown: {countdown}\nMembers who leave: {member_leave}\nThread per exam day: {threads}",
+        "**Instellingen**:\nKanaal: {channel}\nTijd: {time} {timezone}\nFormaat: {format}\nTalen voor inlezen: {locales}\nDatumvolgorde: {order}\nVerzending: {delivery}\nTaal: {language}\nVervolgberichten: {followup}\nAftellen: {countdown}\nVertrekkende leden: {member_leave}\nThread per examendag: {threads}",
+    ),
+    (
+        "language-default",
+        "language of the user",
+        "taal van de gebruiker",
+    ),
+    // /results
+    (
+        "results-empty",
+        "You haven't had any follow-ups after exams in this server yet.",
+        "Je hebt in deze server nog geen vervolgberichten na examens gehad.",
+    ),
+    (
+        "results-header",
+        "How your exams went:",
+        "Hoe je examens gingen:",
+    ),
+    ("results-no-answer", "no answer", "geen antwoord"),
+    // /settings threads
+    (
+        "threads-enabled",
+        "The reminders of each exam day are now sent in a thread under one message",
+        "De herinneringen van elke examendag worden nu in een thread onder één bericht gestuurd",
+    ),
+    (
+        "threads-disabled",
+        "Reminders are now sent in the channel itself",
+        "Herinneringen worden nu in het kanaal zelf gestuurd",
+    ),
+    // Day threads
+    (
+        "day-thread-header",
+        "**Exams on {weekday} {day}**",
+        "**Examens op {weekday} {day}**",
+    ),
+    (
+        "day-thread-name",
+        "Exams on {weekday} {day}",
+        "Examens op {weekday} {day}",
+    ),
+    // /settings member_leave
+    (
+        "member-leave-keep",
+        "Exams of members who leave this server are kept",
+        "Examens van leden die deze server verlaten worden bewaard",
+    ),
+    (
+        "member-leave-pause",
+        "Exams of members who leave this server are paused until they come back",
+        "Examens van leden die deze server verlaten worden gepauzeerd tot ze terugkomen",
+    ),
+    (
+        "member-leave-delete",
+        "Exams of members who leave this server are deleted",
+        "Examens van leden die deze server verlaten worden verwijderd",
+    ),
+    // Reminder channel the bot can't send in
+    (
+        "reminder-channel-changed",
+        "I can't send reminders in {old} on {guild} anymore, so they're sent in {new} from now on. Use `/settings channel` to pick another channel.",
+        "Ik kan geen herinneringen meer sturen in {old} op {guild}, dus ze worden vanaf nu in {new} gestuurd. Gebruik `/instellingen kanaal` om een ander kanaal te kiezen.",
+    ),
+    (
+        "reminder-channel-missing",
+        "I can't send reminders in {old} on {guild} anymore, and there's no other channel I can send them in. Use `/settings channel` to pick one.",
+        "Ik kan geen herinneringen meer sturen in {old} op {guild}, en er is geen ander kanaal waar dat wel kan. Gebruik `/instellingen kanaal` om er een te kiezen.",
+    ),
+    // /countdown
+    (
+        "countdown-empty",
+        "{user} has no upcoming exams",
+        "{user} heeft geen komende examens",
+    ),
+    (
+        "countdown-header",
+        "Countdown for {user}:",
+        "Aftellen voor {user}:",
+    ),
+    (
+        "countdown-duration",
+        "{days}d {hours}h",
+        "{days}d {hours}u",
+    ),
+    (
+        "countdown-soon",
+        "less than an hour",
+        "minder dan een uur",
+    ),
+    (
+        "countdown-live-header",
+        "**Countdown to the next exams**",
+        "**Aftellen tot de volgende examens**",
+    ),
+    (
+        "countdown-live-empty",
+        "No upcoming exams",
+        "Geen komende examens",
+    ),
+    (
+        "countdown-updated",
+        "The pinned countdown in {channel} now lists the next {count} exams",
+        "Het vastgezette aftelbericht in {channel} toont nu de volgende {count} examens",
+    ),
+    (
+        "countdown-disabled",
+        "Removed the pinned countdown",
+        "Het vastgezette aftelbericht is verwijderd",
+    ),
+    // /session
+    (
+        "session-not-found",
+        "There is no session called {session}",
+        "Er is geen sessie met de naam {session}",
+    ),
+    (
+        "session-invalid-range",
+        "A session can't end before it starts",
+        "Een sessie kan niet eindigen voor ze begint",
+    ),
+    (
+        "session-overlap",
+        "This session overlaps with {session}",
+        "Deze sessie overlapt met {session}",
+    ),
+    (
+        "session-duplicate",
+        "There already is a session called {session}",
+        "Er is al een sessie met de naam {session}",
+    ),
+    (
+        "session-added",
+        "Added session {session} from {start} to {end}, with {count} exams",
+        "Sessie {session} van {start} tot {end} toegevoegd, met {count} examens",
+    ),
+    (
+        "session-deleted",
+        "Deleted session {session}",
+        "Sessie {session} verwijderd",
+    ),
+    (
+        "session-list-empty",
+        "This server has no exam sessions yet. Add one with `/session add`.",
+        "Deze server heeft nog geen examensessies. Voeg er een toe met `/sessie toevoegen`.",
+    ),
+    (
+        "session-list-header",
+        "Exam sessions:",
+        "Examensessies:",
+    ),
+    (
+        "session-list-entry",
+        "{session}: {start} to {end} ({done} of {count} exams done)",
+        "{session}: {start} tot {end} ({done} van {count} examens gedaan)",
+    ),
+    (
+        "session-progress",
+        "{user} has done {done} of {count} exams of {session}:",
+        "{user} heeft {done} van {count} examens van {session} gedaan:",
+    ),
+    (
+        "session-progress-empty",
+        "{user} has no exams in {session}",
+        "{user} heeft geen examens in {session}",
+    ),
+    // Session announcements
+    (
+        "session-starts",
+        "The {session} exam session starts tomorrow, with {count} exams. Good luck everyone!",
+        "De examensessie {session} begint morgen, met {count} examens. Veel succes iedereen!",
+    ),
+    (
+        "session-over",
+        "The {session} exam session is over! Well done everyone!",
+        "De examensessie {session} is voorbij! Goed gedaan iedereen!",
+    ),
+    ("setting-on", "on", "aan"),
+    ("setting-off", "off", "uit"),
+    // Follow-up buttons
+    (
+        "followup-saved",
+        "Saved {result}, thanks for letting us know!",
+        "{result} opgeslagen, bedankt voor het laten weten!",
+    ),
+    (
+        "followup-not-yours",
+        "Only the person who took this exam can answer.",
+        "Alleen wie dit examen heeft afgelegd kan antwoorden.",
+    ),
+    // Reminders
+    ("embed-footer", "Exam on {day}", "Examen op {day}"),
+    ("embed-footer-time", "Exam on {day} at {time}", "Examen op {day} om {time}"),
+    (
+        "embed-upcoming",
+        "Other upcoming exams",
+        "Andere komende examens",
+    ),
+];
@@ -1,22 +1,29 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::{sync::Arc, vec};
 
-use chrono::NaiveTime;
 use database::Database;
-use formatter::DEFAULT_FORMAT;
 use log::{debug, info};
-use poise::serenity_prelude::{CommandDataOption, UserId};
+use poise::serenity_prelude::{
+    ChannelType, CommandDataOption, GuildChannel, GuildId, Mentionable, UserId,
+};
 use poise::{FrameworkContext, FrameworkOptions};
 
 use scheduler::Scheduler;
 use serenity::prelude::*;
 
-use crate::database::DbGuild;
+use crate::{
+    database::{DbGuild, MemberLeave},
+    i18n::{guild_language, tr},
+};
 
 mod commands;
+mod countdown;
 mod database;
+mod followup;
 mod formatter;
-mod schedule_parser;
+mod i18n;
+pub mod locale;
+pub mod schedule_parser;
 mod scheduler;
 
 use serenity::model::guild::Guild;
@@ -32,12 +39,14 @@ pub struct Data {
     database: Database,
     scheduler: Arc<Scheduler>,
     parse_interactions: Arc<Mutex<HashMap<UserId, ParseInteraction>>>,
+    // Guilds whose admins were told there's no channel to send reminders in
+    missing_channels: Arc<Mutex<HashSet<GuildId>>>,
 }
 
-fn format_options(options: &Vec<CommandDataOption>) -> String {
+fn format_options(options: &[CommandDataOption]) -> String {
     options
         .iter()
-        .map(|option| format_option(option))
+        .map(format_option)
         .collect::<Vec<String>>()
         .join(",")
 }
@@ -65,6 +74,19 @@ async fn event_handler(
             // Register commands
             poise::builtins::register_globally(ctx, &framework.options().commands).await?;
 
+            // Guilds the bot was removed from while it was offline
+            let data = framework.user_data;
+            for guild_id in data.database.get_guild_ids().await? {
+                if !data_about_bot
+                    .guilds
+                    .iter()
+                    .any(|guild| guild.id == guild_id)
+                {
+                    data.database.set_guild_left(guild_id).await?;
+                }
+            }
+            data.scheduler.load_exams_from_database().await?;
+
             Ok(())
         }
         poise::Event::GuildCreate { guild, .. } => {
@@ -73,17 +95,120 @@ async fn event_handler(
 
             if let Ok(None) = database.get_guild(guild.id).await {
                 let _ = database
-                    .set_guild(DbGuild {
-                        guild_id: guild.id,
-                        message_channel_id: default_channel(ctx, guild).await?,
-                        message_time: NaiveTime::from_hms_opt(21, 0, 0).unwrap(),
-                        message_timezone: chrono_tz::UTC,
-                        format: DEFAULT_FORMAT.to_string(),
-                    })
+                    .set_guild(DbGuild::new(guild.id, default_channel(ctx, guild).await?))
                     .await;
+            } else if database.set_guild_returned(guild.id).await? {
+                // Added back within the grace period, so everything is still there
+                info!("Returned to guild {}", guild.id);
+                framework
+                    .user_data
+                    .scheduler
+                    .load_exams_from_database()
+                    .await?;
+            }
+            Ok(())
+        }
+        poise::Event::GuildDelete { incomplete, .. } => {
+            // Unavailable guilds come back after the outage
+            if incomplete.unavailable {
+                return Ok(());
+            }
+            // The data is deleted once the grace period is over
+            info!("Removed from guild {}", incomplete.id);
+            let data = framework.user_data;
+            data.database.set_guild_left(incomplete.id).await?;
+            data.scheduler.load_exams_from_database().await?;
+            Ok(())
+        }
+        poise::Event::GuildMemberAddition { new_member } => {
+            let data = framework.user_data;
+            // Only the exams that were paused are scheduled again, a full reload would
+            // reschedule reminders that are being sent right now
+            let exam_ids = data
+                .database
+                .set_user_exams_paused(new_member.guild_id, new_member.user.id, false)
+                .await?;
+            for exam_id in exam_ids {
+                data.scheduler.add_exam(exam_id).await?;
+            }
+            Ok(())
+        }
+        poise::Event::GuildMemberRemoval { guild_id, user, .. } => {
+            let data = framework.user_data;
+            let member_leave = match data.database.get_guild(*guild_id).await? {
+                Some(guild) => guild.member_leave,
+                None => return Ok(()),
+            };
+            match member_leave {
+                MemberLeave::Keep => return Ok(()),
+                MemberLeave::Pause => {
+                    data.database
+                        .set_user_exams_paused(*guild_id, user.id, true)
+                        .await?;
+                }
+                MemberLeave::Delete => data.database.delete_user_exams(*guild_id, user.id).await?,
+            }
+            // Group and role exams look up who takes them when they're sent
+            data.scheduler.remove_user_exams(*guild_id, user.id)?;
+            Ok(())
+        }
+        poise::Event::GuildRoleDelete {
+            guild_id,
+            removed_role_id,
+            removed_role_data_if_available,
+        } => {
+            // Exams assigned to the role have nobody left to remind
+            let data = framework.user_data;
+            let exams = data
+                .database
+                .delete_role_exams(*guild_id, *removed_role_id)
+                .await?;
+            if exams.is_empty() {
+                return Ok(());
+            }
+            info!(
+                "Deleted {} exams of removed role {} in {}",
+                exams.len(),
+                removed_role_id,
+                guild_id
+            );
+            data.scheduler.load_exams_from_database().await?;
+
+            if let Some(guild) = data.database.get_guild(*guild_id).await? {
+                let language = guild_language(&guild, &ctx.cache);
+                let role = removed_role_data_if_available
+                    .as_ref()
+                    .map_or_else(|| removed_role_id.to_string(), |role| role.name.clone());
+                let mut message = tr!(language, "role-exams-deleted", role = role);
+                for exam in exams {
+                    if exam.exam_name.is_empty() {
+                        message.push_str(&format!("\n\t{}", exam.day));
+                    } else {
+                        message.push_str(&format!("\n\t{} - {}", exam.day, exam.exam_name));
+                    }
+                }
+                guild.message_channel_id.say(ctx, message).await?;
             }
             Ok(())
         }
+        // The bot may not be able to send reminders in the channel anymore
+        poise::Event::ChannelDelete { channel } => {
+            check_reminder_channel(ctx, framework.user_data, channel.guild_id).await
+        }
+        poise::Event::ChannelUpdate { new, .. } => match new.clone().guild() {
+            Some(channel) => {
+                check_reminder_channel(ctx, framework.user_data, channel.guild_id).await
+            }
+            None => Ok(()),
+        },
+        poise::Event::GuildRoleUpdate { new, .. } => {
+            check_reminder_channel(ctx, framework.user_data, new.guild_id).await
+        }
+        poise::Event::GuildMemberUpdate { new, .. }
+            if new.user.id == ctx.cache.current_user_id() =>
+        {
+            check_reminder_channel(ctx, framework.user_data, new.guild_id).await
+        }
         poise::Event::InteractionCreate { interaction } => {
             if let Some(command) = interaction.clone().application_command() {
                 let options = format_options(&command.data.options);
@@ -92,27 +217,122 @@ async fn event_handler(
                     command.data.name, options
                 );
             }
+            if let Some(component) = interaction.clone().message_component() {
+                followup::handle_button(ctx, &framework.user_data.database, &component).await?;
+            }
             Ok(())
         }
         _ => Ok(()),
     }
 }
 
+fn can_send(ctx: &Context, channel_id: ChannelId) -> bool {
+    ctx.cache
+        .guild_channel(channel_id)
+        .is_some_and(|channel| can_send_in(ctx, &channel))
+}
+
+fn can_send_in(ctx: &Context, channel: &GuildChannel) -> bool {
+    channel
+        .permissions_for_user(ctx, ctx.cache.current_user_id())
+        .map(|perms| perms.view_channel() && perms.send_messages())
+        .unwrap_or(false)
+}
+
+// Moves the reminders to the default channel when the bot can't send them anymore
+async fn check_reminder_channel(
+    ctx: &Context,
+    data: &Data,
+    guild_id: GuildId,
+) -> Result<(), Error> {
+    let mut guild_settings = if let Some(guild_settings) = data.database.get_guild(guild_id).await?
+    {
+        guild_settings
+    } else {
+        return Ok(());
+    };
+    let old_channel_id = guild_settings.message_channel_id;
+    if can_send(ctx, old_channel_id) {
+        data.missing_channels.lock().unwrap().remove(&guild_id);
+        return Ok(());
+    }
+    let guild = if let Some(guild) = ctx.cache.guild(guild_id) {
+        guild
+    } else {
+        return Ok(());
+    };
+
+    let language = guild_language(&guild_settings, &ctx.cache);
+    let message = match default_channel(ctx, &guild).await {
+        Ok(channel_id) => {
+            info!(
+                "Moving reminders in {} from {} to {}",
+                guild_id, old_channel_id, channel_id
+            );
+            guild_settings.message_channel_id = channel_id;
+            data.database.set_guild(guild_settings).await?;
+            data.scheduler.update_countdown(guild_id).await?;
+            tr!(
+                language,
+                "reminder-channel-changed",
+                old = old_channel_id.mention(),
+                guild = guild.name,
+                new = channel_id.mention()
+            )
+        }
+        Err(_) => {
+            // Only once, not on every update until it's fixed
+            if !data.missing_channels.lock().unwrap().insert(guild_id) {
+                return Ok(());
+            }
+            tr!(
+                language,
+                "reminder-channel-missing",
+                old = old_channel_id.mention(),
+                guild = guild.name
+            )
+        }
+    };
+
+    // The system channel, or the owner if the bot can't send messages there
+    match guild
+        .system_channel_id
+        .filter(|&channel_id| can_send(ctx, channel_id))
+    {
+        Some(channel_id) => {
+            channel_id.say(ctx, message).await?;
+        }
+        None => {
+            guild
+                .owner_id
+                .create_dm_channel(ctx)
+                .await?
+                .say(ctx, message)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
 async fn default_channel(ctx: &Context, guild: &Guild) -> Result<ChannelId, Error> {
-    let channel_id = if let Some(channel_id) = guild.system_channel_id {
+    let channel_id = if let Some(channel_id) = guild
+        .system_channel_id
+        .filter(|&channel_id| can_send(ctx, channel_id))
+    {
         channel_id
     } else {
-        guild
+        // The topmost text channel the bot can send in, like Discord shows them
+        let mut channels: Vec<_> = guild
             .channels(&ctx)
             .await?
-            .into_iter()
-            .find(|(_, channel)| {
-                channel
-                    .permissions_for_user(&ctx, ctx.cache.current_user_id())
-                    .and_then(|perms| Ok(perms.send_messages()))
-                    .unwrap_or(false)
-            })
-            .and_then(|(channel_id, _)| Some(channel_id))
+            .into_values()
+            .filter(|channel| channel.kind == ChannelType::Text && can_send_in(ctx, channel))
+            .collect();
+        channels.sort_unstable_by_key(|channel| (channel.position, channel.id));
+        channels
+            .first()
+            .map(|channel| channel.id)
             .ok_or("Couldn't find channel")?
     };
 
@@ -128,6 +348,10 @@ pub async fn run_bot(token: String, database_url: String) -> Result<(), Error> {
                 commands::settings(),
                 commands::exam(),
                 commands::exams(),
+                commands::messages(),
+                commands::results(),
+                commands::countdown(),
+                commands::session(),
                 commands::add_parse_menu(),
                 commands::parse(),
             ],
@@ -142,11 +366,13 @@ pub async fn run_bot(token: String, database_url: String) -> Result<(), Error> {
                     database: database.clone(),
                     scheduler: Scheduler::new(database, ctx.clone()),
                     parse_interactions: Arc::new(Mutex::new(HashMap::new())),
+                    missing_channels: Arc::new(Mutex::new(HashSet::new())),
                 })
             })
         })
         .token(token)
-        .intents(GatewayIntents::GUILDS)
+        // Members are needed to remind everyone with a role
+        .intents(GatewayIntents::GUILDS | GatewayIntents::GUILD_MEMBERS)
         .build()
         .await?;
 
@@ -0,0 +1,231 @@
+use chrono::Weekday;
+
+// Month and weekday vocabularies the schedule parser understands.
+// The first entry of every list is the full name, the rest are abbreviations and alternative spellings.
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Locale {
+    pub code: &'static str,
+    pub name: &'static str,
+    months: [&'static [&'static str]; 12],
+    weekdays: [&'static [&'static str]; 7],
+}
+
+pub const DEFAULT_LOCALES: &str = "nl,en";
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+pub static LOCALES: [Locale; 4] = [
+    Locale {
+        code: "nl",
+        name: "Nederlands",
+        months: [
+            &["januari", "jan"],
+            &["februari", "feb"],
+            &["maart", "mrt", "maa"],
+            &["april", "apr"],
+            &["mei"],
+            &["juni", "jun"],
+            &["juli", "jul"],
+            &["augustus", "aug"],
+            &["september", "sep", "sept"],
+            &["oktober", "okt"],
+            &["november", "nov"],
+            &["december", "dec"],
+        ],
+        weekdays: [
+            &["maandag", "ma"],
+            &["dinsdag", "di"],
+            &["woensdag", "wo"],
+            &["donderdag", "do"],
+            &["vrijdag", "vr"],
+            &["zaterdag", "za"],
+            &["zondag", "zo"],
+        ],
+    },
+    Locale {
+        code: "en",
+        name: "English",
+        months: [
+            &["january", "jan"],
+            &["february", "feb"],
+            &["march", "mar"],
+            &["april", "apr"],
+            &["may"],
+            &["june", "jun"],
+            &["july", "jul"],
+            &["august", "aug"],
+            &["september", "sep", "sept"],
+            &["october", "oct"],
+            &["november", "nov"],
+            &["december", "dec"],
+        ],
+        weekdays: [
+            &["monday", "mon"],
+            &["tuesday", "tue", "tues"],
+            &["wednesday", "wed"],
+            &["thursday", "thu", "thurs"],
+            &["friday", "fri"],
+            &["saturday", "sat"],
+            &["sunday", "sun"],
+        ],
+    },
+    Locale {
+        code: "fr",
+        name: "Français",
+        months: [
+            &["janvier", "janv"],
+            &["février", "fevrier", "févr", "fevr", "fév", "fev"],
+            &["mars"],
+            &["avril", "avr"],
+            &["mai"],
+            &["juin"],
+            &["juillet", "juil"],
+            &["août", "aout"],
+            &["septembre", "sept"],
+            &["octobre", "oct"],
+            &["novembre", "nov"],
+            &["décembre", "decembre", "déc", "dec"],
+        ],
+        weekdays: [
+            &["lundi", "lun"],
+            &["mardi", "mar"],
+            &["mercredi", "mer"],
+            &["jeudi", "jeu"],
+            &["vendredi", "ven"],
+            &["samedi", "sam"],
+            &["dimanche", "dim"],
+        ],
+    },
+    Locale {
+        code: "de",
+        name: "Deutsch",
+        months: [
+            &["januar", "jän", "jan"],
+            &["februar", "feb"],
+            &["märz", "maerz", "mär"],
+            &["april", "apr"],
+            &["mai"],
+            &["juni", "jun"],
+            &["juli", "jul"],
+            &["august", "aug"],
+            &["september", "sep", "sept"],
+            &["oktober", "okt"],
+            &["november", "nov"],
+            &["dezember", "dez"],
+        ],
+        weekdays: [
+            &["montag", "mo"],
+            &["dienstag", "di"],
+            &["mittwoch", "mi"],
+            &["donnerstag", "do"],
+            &["freitag", "fr"],
+            &["samstag", "sa"],
+            &["sonntag", "so"],
+        ],
+    },
+];
+
+impl Locale {
+    // Month number (1-12) for a month name in this locale
+    pub fn month(&self, text: &str) -> Option<u32> {
+        let text = text.to_lowercase();
+        self.months
+            .iter()
+            .position(|names| names.contains(&text.as_str()))
+            .map(|i| i as u32 + 1)
+    }
+
+    pub fn weekday(&self, text: &str) -> Option<Weekday> {
+        let text = text.to_lowercase();
+        self.weekdays
+            .iter()
+            .position(|names| names.contains(&text.as_str()))
+            .map(|i| WEEKDAYS[i])
+    }
+
+    // Full name of a month (1-12)
+    pub fn month_name(&self, month: u32) -> &'static str {
+        self.months[month as usize - 1][0]
+    }
+
+    pub fn weekday_name(&self, weekday: Weekday) -> &'static str {
+        self.weekdays[weekday.num_days_from_monday() as usize][0]
+    }
+}
+
+pub fn find_locale(code: &str) -> Option<&'static Locale> {
+    let code = code.trim().to_lowercase();
+    LOCALES.iter().find(|locale| locale.code == code)
+}
+
+// Parses a comma separated list of locale codes, returning the codes that don't exist as the error
+pub fn parse_locales(codes: &str) -> Result<Vec<&'static Locale>, Vec<String>> {
+    let mut locales = Vec::new();
+    let mut unknown = Vec::new();
+    for code in codes.split(',').filter(|code| !code.trim().is_empty()) {
+        match find_locale(code) {
+            Some(locale) if !locales.contains(&locale) => locales.push(locale),
+            Some(_) => {}
+            None => unknown.push(code.trim().to_owned()),
+        }
+    }
+
+    if unknown.is_empty() {
+        Ok(locales)
+    } else {
+        Err(unknown)
+    }
+}
+
+pub fn format_locales(locales: &[&Locale]) -> String {
+    locales
+        .iter()
+        .map(|locale| locale.code)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// Shorter words like "en" are too far from every month name to be a typo of one
+const MIN_SUGGEST_LENGTH: usize = 4;
+
+// Closest full month name to a misspelled month, if it's close enough to be a typo:
+// at most one edit per three characters of the text
+pub fn suggest_month(locales: &[&Locale], text: &str) -> Option<&'static str> {
+    let text = text.to_lowercase();
+    let length = text.chars().count();
+    if length < MIN_SUGGEST_LENGTH {
+        return None;
+    }
+    locales
+        .iter()
+        .flat_map(|locale| locale.months.iter())
+        .map(|names| names[0])
+        .map(|full_name| (full_name, edit_distance(&text, full_name)))
+        .filter(|(_, distance)| distance * 3 <= length)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(full_name, _)| full_name)
+}
+
+// Levenshtein distance
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
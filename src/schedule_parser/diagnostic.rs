@@ -0,0 +1,283 @@
+// Diagnostics produced while parsing a schedule, and how to render them.
+// Spans and edits are byte offsets into `source`, the line (or comma separated part) the diagnostic is about.
+
+use std::{error::Error, fmt::Display};
+
+use serde::{Serialize, Serializer};
+
+use super::Token;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+// Every kind of diagnostic has a code that doesn't change when the message does
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ErrorCode {
+    NoExam,
+    UnknownMonth,
+    InvalidMonth,
+    InvalidDate,
+    SwappedDateOrder,
+    WeekdayMismatch,
+    RangeTooLong,
+    InvalidTime,
+    MultipleDetails,
+    AssumedDate,
+    NotInTable,
+    NoDateInRow,
+    NoDateInCell,
+}
+
+impl ErrorCode {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorCode::NoExam => "P001",
+            ErrorCode::UnknownMonth => "P002",
+            ErrorCode::InvalidMonth => "P003",
+            ErrorCode::InvalidDate => "P004",
+            ErrorCode::SwappedDateOrder => "P005",
+            ErrorCode::WeekdayMismatch => "P006",
+            ErrorCode::RangeTooLong => "P007",
+            ErrorCode::InvalidTime => "P008",
+            ErrorCode::MultipleDetails => "P009",
+            ErrorCode::AssumedDate => "P010",
+            ErrorCode::NotInTable => "P011",
+            ErrorCode::NoDateInRow => "P012",
+            ErrorCode::NoDateInCell => "P013",
+        }
+    }
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl Serialize for ErrorCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub label: Option<String>,
+}
+
+// Replaces `start..end` of the source with `replacement`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Edit {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Suggestion {
+    pub message: String,
+    pub edit: Option<Edit>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ParseError {
+    pub code: ErrorCode,
+    pub severity: Severity,
+    pub message: String,
+    // Where the schedule came from when a message has several (an attachment, an embed)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    // Zero based position of the primary span (or the start of the source) in the schedule
+    pub line: usize,
+    pub column: usize,
+    pub source: String,
+    pub primary: Option<Span>,
+    pub secondary: Vec<Span>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl ParseError {
+    pub(super) fn new(
+        code: ErrorCode,
+        severity: Severity,
+        token: &Token,
+        message: impl Into<String>,
+    ) -> Self {
+        ParseError {
+            code,
+            severity,
+            message: message.into(),
+            origin: None,
+            line: token.line_nr,
+            column: token.column_nr,
+            source: token.text.to_owned(),
+            primary: None,
+            secondary: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    pub(super) fn warning(code: ErrorCode, token: &Token, message: impl Into<String>) -> Self {
+        ParseError::new(code, Severity::Warning, token, message)
+    }
+
+    pub(super) fn error(code: ErrorCode, token: &Token, message: impl Into<String>) -> Self {
+        ParseError::new(code, Severity::Error, token, message)
+    }
+
+    pub(super) fn primary(mut self, start: usize, end: usize) -> Self {
+        self.column += start;
+        self.primary = Some(Span {
+            start,
+            end,
+            label: None,
+        });
+        self
+    }
+
+    pub(super) fn secondary(mut self, start: usize, end: usize, label: impl Into<String>) -> Self {
+        self.secondary.push(Span {
+            start,
+            end,
+            label: Some(label.into()),
+        });
+        self
+    }
+
+    pub(super) fn suggestion(mut self, message: impl Into<String>) -> Self {
+        self.suggestions.push(Suggestion {
+            message: message.into(),
+            edit: None,
+        });
+        self
+    }
+
+    pub(super) fn replacement(
+        mut self,
+        message: impl Into<String>,
+        start: usize,
+        end: usize,
+        replacement: impl Into<String>,
+    ) -> Self {
+        self.suggestions.push(Suggestion {
+            message: message.into(),
+            edit: Some(Edit {
+                start,
+                end,
+                replacement: replacement.into(),
+            }),
+        });
+        self
+    }
+
+    // The source with a line of markers under every span:
+    // ^ for the primary span, - for secondary spans
+    fn annotated_source(&self) -> String {
+        let mut lines = vec![self.source.clone()];
+        let spans = self
+            .primary
+            .iter()
+            .map(|span| (span, '^'))
+            .chain(self.secondary.iter().map(|span| (span, '-')));
+        for (span, marker) in spans {
+            if span.end <= span.start || span.end > self.source.len() {
+                continue;
+            }
+            // Offsets are in bytes, markers are placed per character
+            let padding = self.source[..span.start].chars().count();
+            let width = self.source[span.start..span.end].chars().count();
+            let mut line = format!(
+                "{}{}",
+                " ".repeat(padding),
+                marker.to_string().repeat(width)
+            );
+            if let Some(label) = &span.label {
+                line += " ";
+                line += label;
+            }
+            lines.push(line);
+        }
+        lines.join("\n")
+    }
+
+    fn help_lines(&self) -> Vec<String> {
+        self.suggestions
+            .iter()
+            .map(|suggestion| match &suggestion.edit {
+                Some(edit) => {
+                    let mut fixed = self.source.clone();
+                    fixed.replace_range(edit.start..edit.end, &edit.replacement);
+                    format!("help: {}: `{}`", suggestion.message, fixed)
+                }
+                None => format!("help: {}", suggestion.message),
+            })
+            .collect()
+    }
+
+    fn location(&self) -> String {
+        let location = format!("line {}, column {}", self.line + 1, self.column + 1);
+        match &self.origin {
+            Some(origin) => format!("{}, {}", origin, location),
+            None => location,
+        }
+    }
+
+    // Markdown for a Discord message
+    pub fn render_discord(&self) -> String {
+        let mut message = format!(
+            "**{} {} while parsing: {}**\n -> {}",
+            self.severity,
+            self.code,
+            self.message,
+            self.location()
+        );
+        if !self.source.is_empty() {
+            message += &format!("\n```\n{}\n```", self.annotated_source());
+        }
+        for help in self.help_lines() {
+            message += "\n";
+            message += &help;
+        }
+        message
+    }
+}
+
+// Plain text, for terminals and logs
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {}\n --> {}",
+            self.severity,
+            self.code,
+            self.message,
+            self.location()
+        )?;
+        if !self.source.is_empty() {
+            for line in self.annotated_source().lines() {
+                write!(f, "\n  | {}", line)?;
+            }
+        }
+        for help in self.help_lines() {
+            write!(f, "\n  = {}", help)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseError {}
@@ -0,0 +1,807 @@
+use std::{fmt::Display, ops::Range};
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, Utc};
+use log::debug;
+use once_cell::sync::Lazy;
+use poise::serenity_prelude::UserId;
+use regex::{Captures, Regex};
+use serde::Serialize;
+
+mod diagnostic;
+mod table;
+
+pub use diagnostic::{Edit, ErrorCode, ParseError, Severity, Span, Suggestion};
+
+use crate::{
+    database::DbGuild,
+    locale::{self, suggest_month, Locale},
+};
+
+static EXAM_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:(\d{1,2}) (\w*)|(\d{1,2})[/-](\d{1,2}))[:\- ]+(.*)").unwrap());
+
+// 12-14 juni, 30 jan - 2 feb, 12 t/m 14 juni
+// Only at the start of the line (after an optional weekday), so "5 jan: H1-3 en 5" isn't a range
+static RANGE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*(?:[^\W\d_]+[\s,.]*)?(\d{1,2})(?: (\w+))?\s*(?:-|–|t/m|tot|to|until)\s*(\d{1,2}) (\w+)[:\- ]+(.*)")
+        .unwrap()
+});
+
+// 12/6 - 14/6, with the same separators as EXAM_REGEX
+static NUMERIC_RANGE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*(?:[^\W\d_]+[\s,.]*)?(\d{1,2})[/-](\d{1,2})\s*(?:-|–|t/m|tot|to|until)\s*(\d{1,2})[/-](\d{1,2})[:\- ]+(.*)")
+        .unwrap()
+});
+
+// <@123> or <@!123>
+static MENTION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"<@!?(\d+)>").unwrap());
+// What can stand between mentions of users taking the same exam ("@Alice & @Bob")
+static MENTION_SEPARATOR_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:[\s,&+]|en|and)*$").unwrap());
+
+// The word in front of the date, e.g. "ma" in "ma 12 jan"
+static WEEKDAY_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\w+)[\s,.]*$").unwrap());
+
+// 9u, 9h30, 14:00, optionally followed by an end time (9h30-12h30, 9u tot 12u)
+static TIME_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(\d{1,2})(?:[hu](\d{2})?|:(\d{2}))(?:\s*(?:-|–|tot|to)\s*(\d{1,2})(?:[hu](\d{2})?|:(\d{2})))?\b")
+        .unwrap()
+});
+
+// @ 200C 00.01, lokaal 00.225, room B, (aula A)
+static LOCATION_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)(?:(@)\s*|\b(?:lokaal|room|aula|auditorium|locatie|location)\b[:\s]*)([^(),;|]*)",
+    )
+    .unwrap()
+});
+
+// Leftovers after cutting the time and location out of the name, e.g. "()" or "(, )"
+static EMPTY_GROUP_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\([\s,;:\-]*\)|\[[\s,;:\-]*\]").unwrap());
+static WHITESPACE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s{2,}").unwrap());
+
+// How numeric dates like 3/4 are read
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum DateOrder {
+    #[default]
+    #[name = "day/month"]
+    #[name = "dmy"]
+    #[name_localized("nl", "dag/maand")]
+    DayMonth,
+    #[name = "month/day"]
+    #[name = "mdy"]
+    #[name_localized("nl", "maand/dag")]
+    MonthDay,
+}
+
+impl DateOrder {
+    // Name stored in the database
+    pub fn code(&self) -> &'static str {
+        match self {
+            DateOrder::DayMonth => "dmy",
+            DateOrder::MonthDay => "mdy",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+    pub locales: Vec<&'static Locale>,
+    pub date_order: DateOrder,
+    // Dates without a year are the first one on or after this day
+    pub today: NaiveDate,
+}
+
+impl ParseOptions {
+    // Default languages and date order
+    pub fn new(today: NaiveDate) -> Self {
+        ParseOptions {
+            locales: locale::parse_locales(locale::DEFAULT_LOCALES).unwrap(),
+            date_order: DateOrder::default(),
+            today,
+        }
+    }
+
+    // The guild's settings, with today being the date in the guild's timezone at `now`
+    pub fn for_guild(guild: &DbGuild, now: DateTime<Utc>) -> Self {
+        ParseOptions {
+            locales: guild.parse_locales.clone(),
+            date_order: guild.date_order,
+            today: now.with_timezone(&guild.message_timezone).date_naive(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize)]
+pub struct ExamTime {
+    pub start: NaiveTime,
+    pub end: Option<NaiveTime>,
+}
+
+impl Display for ExamTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.start.format("%H:%M"))?;
+        if let Some(end) = self.end {
+            write!(f, "-{}", end.format("%H:%M"))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+pub struct ParseExam {
+    pub day: NaiveDate,
+    // Last day of an exam that spans several days
+    pub last_day: Option<NaiveDate>,
+    pub name: String,
+    pub time: Option<ExamTime>,
+    pub location: Option<String>,
+    // User mentioned in front of the exam, if any
+    pub user: Option<UserId>,
+    // Custom reminder message, only set while reviewing the parsed exams in the bot
+    #[serde(skip)]
+    pub message: Option<String>,
+}
+
+impl Display for ParseExam {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.day)?;
+        if let Some(last_day) = self.last_day {
+            write!(f, " to {}", last_day)?;
+        }
+        if let Some(time) = self.time {
+            write!(f, " {}", time)?;
+        }
+        write!(f, " - {}", self.name)?;
+        if let Some(location) = &self.location {
+            write!(f, " @ {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct Token {
+    text: String,
+    line_nr: usize,
+    column_nr: usize,
+}
+
+// The text of a single date within a token
+enum DateText<'t> {
+    Named {
+        day: regex::Match<'t>,
+        month: regex::Match<'t>,
+    },
+    Numeric {
+        first: regex::Match<'t>,
+        second: regex::Match<'t>,
+    },
+}
+
+impl DateText<'_> {
+    fn start(&self) -> usize {
+        match self {
+            DateText::Named { day, .. } => day.start(),
+            DateText::Numeric { first, .. } => first.start(),
+        }
+    }
+
+    fn end(&self) -> usize {
+        match self {
+            DateText::Named { month, .. } => month.end(),
+            DateText::Numeric { second, .. } => second.end(),
+        }
+    }
+}
+
+// Reads a time from the hour group at `group` and the two alternative minute groups after it
+fn capture_time(captures: &Captures, group: usize) -> Option<NaiveTime> {
+    let hour = captures.get(group)?.as_str().parse().ok()?;
+    let minute = match captures.get(group + 1).or(captures.get(group + 2)) {
+        Some(minute) => minute.as_str().parse().ok()?,
+        None => 0,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+// Splits a token on user mentions, every part is for the users mentioned in front of it.
+// A part with no text means the token only mentions users.
+fn split_mentions(token: Token) -> Vec<(Vec<UserId>, Token)> {
+    // Mentions that directly follow each other belong together
+    let mut groups: Vec<(Vec<UserId>, Range<usize>)> = Vec::new();
+    for captures in MENTION_REGEX.captures_iter(&token.text) {
+        let mention = captures.get(0).unwrap();
+        let user = match captures.get(1).unwrap().as_str().parse() {
+            Ok(user_id) => UserId(user_id),
+            Err(_) => continue,
+        };
+        match groups.last_mut() {
+            Some((users, range))
+                if MENTION_SEPARATOR_REGEX.is_match(&token.text[range.end..mention.start()]) =>
+            {
+                users.push(user);
+                range.end = mention.end();
+            }
+            _ => groups.push((vec![user], mention.range())),
+        }
+    }
+
+    if groups.is_empty() {
+        return vec![(Vec::new(), token)];
+    }
+
+    let part = |start: usize, end: usize| {
+        let text = &token.text[start..end];
+        let trimmed = text.trim_start_matches(|c: char| c.is_whitespace() || ":/,;|-–".contains(c));
+        Token {
+            text: trimmed
+                .trim_end_matches(|c: char| c.is_whitespace() || ":/,;|-–".contains(c))
+                .to_owned(),
+            line_nr: token.line_nr,
+            column_nr: token.column_nr + start + text.len() - trimmed.len(),
+        }
+    };
+
+    let mut parts = Vec::new();
+    if groups[0].1.start > 0 {
+        parts.push((Vec::new(), part(0, groups[0].1.start)));
+    }
+    for (i, (users, range)) in groups.iter().enumerate() {
+        let end = groups
+            .get(i + 1)
+            .map_or(token.text.len(), |(_, next)| next.start);
+        let text = part(range.end, end);
+        if text.text.is_empty() {
+            // Mentioned after the exam ("12 jan Algebra @Alice")
+            if let Some((previous_users, _)) = parts.last_mut() {
+                if previous_users.is_empty() {
+                    *previous_users = users.clone();
+                    continue;
+                }
+            }
+        }
+        parts.push((users.clone(), text));
+    }
+    parts
+}
+
+// One exam per mentioned user, or a single exam if nobody was mentioned
+fn owners(users: &[UserId]) -> Vec<Option<UserId>> {
+    if users.is_empty() {
+        vec![None]
+    } else {
+        users.iter().copied().map(Some).collect()
+    }
+}
+
+// Finds the time in a piece of text, returning it along with where it was found in `text`.
+// `offset` is where `text` starts in the token.
+fn parse_time(
+    exam: &Token,
+    text: &str,
+    offset: usize,
+    warnings: &mut Vec<ParseError>,
+) -> Option<(ExamTime, Range<usize>)> {
+    let time_captures = TIME_REGEX.captures(text)?;
+    let range = time_captures.get(0).unwrap().range();
+    let start = capture_time(&time_captures, 1);
+    let end = time_captures
+        .get(4)
+        .map(|_| capture_time(&time_captures, 4));
+
+    match (start, end) {
+        (Some(start), None) => Some((ExamTime { start, end: None }, range)),
+        (Some(start), Some(Some(end))) => Some((
+            ExamTime {
+                start,
+                end: Some(end),
+            },
+            range,
+        )),
+        _ => {
+            warnings.push(
+                ParseError::warning(
+                    ErrorCode::InvalidTime,
+                    exam,
+                    "Could not parse time, it was left in the exam name.",
+                )
+                .primary(offset + range.start, offset + range.end)
+                .suggestion("write times as 9u, 9h30 or 14:00, with hours below 24"),
+            );
+            None
+        }
+    }
+}
+
+// Splits the text after the date into the exam names, time and location.
+// `offset` is where `text` starts in the token.
+fn parse_details(
+    exam: &Token,
+    text: &str,
+    offset: usize,
+    warnings: &mut Vec<ParseError>,
+) -> (Vec<String>, Option<ExamTime>, Option<String>) {
+    let mut name = text.to_owned();
+
+    let time = parse_time(exam, &name, offset, warnings).map(|(time, range)| {
+        name.replace_range(range, " ");
+        time
+    });
+
+    let mut location = None;
+    if let Some(location_captures) = LOCATION_REGEX.captures(&name) {
+        let full_match = location_captures.get(0).unwrap();
+        let text = location_captures.get(2).unwrap().as_str().trim();
+        if !text.is_empty() {
+            // "@" is only a marker, keywords like "aula" are part of the location
+            location = Some(if location_captures.get(1).is_some() {
+                text.to_owned()
+            } else {
+                full_match.as_str().trim().to_owned()
+            });
+            let range = full_match.range();
+            name.replace_range(range, " ");
+        }
+    }
+
+    // Only one time and location are used for the whole line
+    if time.is_some() && TIME_REGEX.is_match(&name)
+        || location.is_some() && LOCATION_REGEX.is_match(&name)
+    {
+        warnings.push(
+            ParseError::warning(
+                ErrorCode::MultipleDetails,
+                exam,
+                "This line has more than one time or location, only the first one was used for all exams on it.",
+            )
+            .primary(offset, offset + text.len())
+            .suggestion("put exams with a different time or location on their own line"),
+        );
+    }
+
+    let name = MENTION_REGEX.replace_all(&name, " ");
+    let name = EMPTY_GROUP_REGEX.replace_all(&name, " ");
+    let name = WHITESPACE_REGEX.replace_all(&name, " ");
+    let names = split_names(&name)
+        .into_iter()
+        .map(|name| {
+            name.trim_matches(|c: char| c.is_whitespace() || ",;:-–|+/".contains(c))
+                .to_owned()
+        })
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>();
+
+    if names.is_empty() {
+        // Nameless exam
+        (vec![String::new()], time, location)
+    } else {
+        (names, time, location)
+    }
+}
+
+// Splits "Algebra, Analyse" into separate exams, ignoring separators between parentheses
+fn split_names(text: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' | ';' | '+' if depth <= 0 => {
+                names.push(&text[start..i]);
+                start = i + c.len_utf8();
+            }
+            '/' if depth <= 0 && text[i + 1..].starts_with(' ') => {
+                names.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    names.push(&text[start..]);
+    names
+}
+
+// Reads the day and month of a date. Returns None and adds an error if it isn't a valid date.
+fn parse_day_month(
+    exam: &Token,
+    date: &DateText,
+    options: &ParseOptions,
+    warnings: &mut Vec<ParseError>,
+) -> Option<(u32, u32)> {
+    match date {
+        DateText::Named { day, month } => {
+            let day = day.as_str().parse().ok()?;
+            match options
+                .locales
+                .iter()
+                .find_map(|locale| locale.month(month.as_str()))
+            {
+                Some(month) => Some((day, month)),
+                None => {
+                    let error =
+                        ParseError::error(ErrorCode::UnknownMonth, exam, "Could not parse month.")
+                            .primary(month.start(), month.end());
+                    warnings.push(match suggest_month(&options.locales, month.as_str()) {
+                        Some(suggestion) => error.replacement(
+                            format!("did you mean \"{}\"", suggestion),
+                            month.start(),
+                            month.end(),
+                            suggestion,
+                        ),
+                        None => error,
+                    });
+                    None
+                }
+            }
+        }
+        DateText::Numeric { first, second } => {
+            // The guild decides which of the numbers is the day
+            let (day_capture, month_capture) = match options.date_order {
+                DateOrder::DayMonth => (first, second),
+                DateOrder::MonthDay => (second, first),
+            };
+            let mut day = day_capture.as_str().parse().ok()?;
+            let mut month = month_capture.as_str().parse().ok()?;
+            if !(1..=12).contains(&month) {
+                if (1..=12).contains(&day) {
+                    // Only the other order makes sense (4/13 on a day/month server)
+                    let other_order = match options.date_order {
+                        DateOrder::DayMonth => DateOrder::MonthDay,
+                        DateOrder::MonthDay => DateOrder::DayMonth,
+                    };
+                    warnings.push(
+                        ParseError::warning(
+                            ErrorCode::SwappedDateOrder,
+                            exam,
+                            format!(
+                                "{} is not a valid month, so this date was read as {} instead of {}.",
+                                month, other_order, options.date_order
+                            ),
+                        )
+                        .primary(date.start(), date.end())
+                        .secondary(
+                            month_capture.start(),
+                            month_capture.end(),
+                            "not a month",
+                        )
+                        .suggestion(format!(
+                            "use `/settings date_order` if this server writes dates as {}",
+                            other_order
+                        )),
+                    );
+                    std::mem::swap(&mut day, &mut month);
+                } else {
+                    warnings.push(
+                        ParseError::error(ErrorCode::InvalidMonth, exam, "Invalid month.")
+                            .primary(month_capture.start(), month_capture.end()),
+                    );
+                    return None;
+                }
+            }
+            Some((day, month))
+        }
+    }
+}
+
+// Turns a date into a NaiveDate on or after `after`, unless the schedule wrote down the year
+fn resolve_date(
+    exam: &Token,
+    date: &DateText,
+    options: &ParseOptions,
+    after: NaiveDate,
+    year: Option<i32>,
+    warnings: &mut Vec<ParseError>,
+) -> Option<NaiveDate> {
+    let (day, month) = parse_day_month(exam, date, options, warnings)?;
+
+    // Ensure the date is in the future :)
+    let year = year.unwrap_or(if (month, day) >= (after.month(), after.day()) {
+        after.year()
+    } else {
+        after.year() + 1
+    });
+
+    let resolved = NaiveDate::from_ymd_opt(year, month, day);
+    if resolved.is_none() {
+        let month_name = options.locales.first().map_or(month.to_string(), |locale| {
+            locale.month_name(month).to_owned()
+        });
+        warnings.push(
+            ParseError::error(
+                ErrorCode::InvalidDate,
+                exam,
+                format!("Invalid date, {} has no day {}.", month_name, day),
+            )
+            .primary(date.start(), date.end()),
+        );
+    }
+    resolved
+}
+
+// A schedule found in a message, from its content, an attachment or an embed
+pub struct Source {
+    pub name: String,
+    pub text: String,
+}
+
+// Parses every source on its own, diagnostics keep the line numbers within their source
+pub fn parse_sources(
+    sources: &[Source],
+    options: &ParseOptions,
+) -> Result<(Vec<ParseExam>, Vec<ParseError>), crate::Error> {
+    let mut exams = Vec::new();
+    let mut warnings = Vec::new();
+    for source in sources {
+        let (mut source_exams, source_warnings) = parse(&source.text, options)?;
+        exams.append(&mut source_exams);
+        warnings.extend(source_warnings.into_iter().map(|warning| ParseError {
+            origin: Some(source.name.clone()),
+            ..warning
+        }));
+    }
+    Ok((exams, warnings))
+}
+
+// Returns
+pub fn parse(
+    schedule: &str,
+    options: &ParseOptions,
+) -> Result<(Vec<ParseExam>, Vec<ParseError>), crate::Error> {
+    let locales = &options.locales;
+    let mut exams: Vec<_> = Vec::new();
+    let mut warnings: Vec<_> = Vec::new();
+
+    // Split the schedule into seperate exams
+    // A trailing newline doesn't make it a list of lines
+    let comma_separated = !schedule.trim_end().contains('\n');
+    let schedule: Vec<_> = if !comma_separated {
+        // schedule has newlines, split on those
+        schedule
+            .split('\n')
+            .enumerate()
+            .map(|(i, s)| {
+                let trimmed_clean = s.replace("```", "");
+                let trimmed_start = trimmed_clean.trim_start();
+                let trimmed = trimmed_start.trim_end();
+                let trim_offset = s.len() - trimmed_start.len();
+                Token {
+                    text: trimmed.to_owned(),
+                    line_nr: i,
+                    column_nr: trim_offset,
+                }
+            })
+            .filter(|token| !token.text.is_empty())
+            .collect()
+    } else {
+        // schedule has commas, split on those
+        // schedule.split(",").enumerate().map(|(i, s)| Token {text: s, line: i, column: 0})
+        let mut column = 0;
+        schedule
+            .split(',')
+            .map(|s| {
+                // Remove backticks (they mess with our own messages)
+                let trimmed_clean = s.replace('`', "");
+                let trimmed_start = trimmed_clean.trim_start();
+                let trimmed = trimmed_start.trim_end();
+                let trim_offset = s.len() - trimmed_start.len();
+                let tok = Token {
+                    text: trimmed.to_owned(),
+                    line_nr: 0,
+                    column_nr: column + trim_offset,
+                };
+                column += s.len();
+                tok
+            })
+            .filter(|token| !token.text.is_empty())
+            .collect()
+    };
+
+    let today = options.today;
+
+    if !comma_separated {
+        if let Some(exams) = table::parse_table(&schedule, options, &mut warnings) {
+            return Ok((exams, warnings));
+        }
+    }
+
+    // A line that only mentions users assigns the lines below it to them
+    let mut section_users = Vec::new();
+    let mut lines = Vec::new();
+    for token in schedule {
+        let parts = split_mentions(token);
+        if let [(users, part)] = parts.as_slice() {
+            if part.text.is_empty() {
+                section_users = users.clone();
+                continue;
+            }
+        }
+        for (users, part) in parts {
+            if part.text.is_empty() {
+                continue;
+            }
+            if users.is_empty() {
+                lines.push((section_users.clone(), part));
+            } else {
+                lines.push((users, part));
+            }
+        }
+    }
+
+    // Dates of the last exam, for exams without a date in a comma separated schedule
+    let mut previous_dates: Option<(NaiveDate, Option<NaiveDate>)> = None;
+
+    for (users, exam) in lines {
+        let (first_date, last_date, details) =
+            if let Some(captures) = RANGE_REGEX.captures(exam.text.as_str()) {
+                let last_month = captures.get(4).unwrap();
+                (
+                    DateText::Named {
+                        day: captures.get(1).unwrap(),
+                        month: captures.get(2).unwrap_or(last_month),
+                    },
+                    Some(DateText::Named {
+                        day: captures.get(3).unwrap(),
+                        month: last_month,
+                    }),
+                    captures.get(5).unwrap(),
+                )
+            } else if let Some(captures) = NUMERIC_RANGE_REGEX.captures(exam.text.as_str()) {
+                (
+                    DateText::Numeric {
+                        first: captures.get(1).unwrap(),
+                        second: captures.get(2).unwrap(),
+                    },
+                    Some(DateText::Numeric {
+                        first: captures.get(3).unwrap(),
+                        second: captures.get(4).unwrap(),
+                    }),
+                    captures.get(5).unwrap(),
+                )
+            } else if let Some(captures) = EXAM_REGEX.captures(exam.text.as_str()) {
+                debug!("Exam captures: {:?}", captures);
+
+                let date = match (captures.get(1), captures.get(2)) {
+                    (Some(day), Some(month)) => DateText::Named { day, month },
+                    _ => DateText::Numeric {
+                        first: captures.get(3).unwrap(),
+                        second: captures.get(4).unwrap(),
+                    },
+                };
+                (date, None, captures.get(5).unwrap())
+            } else {
+                match previous_dates {
+                    Some((day, last_day)) if comma_separated => {
+                        // "5 jan: Algebra, Analyse" was split on the comma
+                        warnings.push(
+                            ParseError::warning(
+                                ErrorCode::AssumedDate,
+                                &exam,
+                                format!("No date found, assumed this is another exam on {}.", day),
+                            )
+                            .suggestion("put the date in front if this exam is on another day"),
+                        );
+                        let (names, time, location) =
+                            parse_details(&exam, &exam.text, 0, &mut warnings);
+                        for name in names {
+                            for user in owners(&users) {
+                                exams.push(ParseExam {
+                                    day,
+                                    last_day,
+                                    name: name.clone(),
+                                    time,
+                                    location: location.clone(),
+                                    user,
+                                    message: None,
+                                });
+                            }
+                        }
+                    }
+                    _ => warnings.push(
+                        ParseError::warning(ErrorCode::NoExam, &exam, "Could not match an exam.")
+                            .suggestion("start the line with a date, like `12 jan Algebra`"),
+                    ),
+                }
+                continue;
+            };
+
+        let exam_date = match resolve_date(&exam, &first_date, options, today, None, &mut warnings)
+        {
+            Some(exam_date) => exam_date,
+            None => continue,
+        };
+
+        let last_day = if let Some(last_date) = &last_date {
+            let last_day =
+                match resolve_date(&exam, last_date, options, exam_date, None, &mut warnings) {
+                    Some(last_day) => last_day,
+                    None => continue,
+                };
+            if (last_day - exam_date).num_days() > 31 {
+                warnings.push(
+                    ParseError::error(
+                        ErrorCode::RangeTooLong,
+                        &exam,
+                        format!(
+                            "This exam would span {} days, check if the dates are correct.",
+                            (last_day - exam_date).num_days() + 1
+                        ),
+                    )
+                    .primary(first_date.start(), first_date.end())
+                    .secondary(
+                        last_date.start(),
+                        last_date.end(),
+                        format!("read as {}", last_day),
+                    ),
+                );
+                continue;
+            }
+            // "12-12 juni" is just one day
+            Some(last_day).filter(|last_day| *last_day != exam_date)
+        } else {
+            None
+        };
+        let date_index_end = last_date.as_ref().unwrap_or(&first_date).end();
+
+        // A weekday in front of the date has to agree with the date itself
+        if let Some(weekday_captures) = WEEKDAY_REGEX.captures(&exam.text[..first_date.start()]) {
+            let weekday_text = weekday_captures.get(1).unwrap();
+            if let Some((locale, weekday)) = locales.iter().find_map(|locale| {
+                locale
+                    .weekday(weekday_text.as_str())
+                    .map(|weekday| (locale, weekday))
+            }) {
+                if weekday != exam_date.weekday() {
+                    let actual_weekday = locale.weekday_name(exam_date.weekday());
+                    warnings.push(
+                        ParseError::warning(
+                            ErrorCode::WeekdayMismatch,
+                            &exam,
+                            format!(
+                                "{} is a {}, not a {}. Check if the date is correct.",
+                                exam_date,
+                                actual_weekday,
+                                locale.weekday_name(weekday)
+                            ),
+                        )
+                        .primary(weekday_text.start(), weekday_text.end())
+                        .secondary(
+                            first_date.start(),
+                            date_index_end,
+                            format!("this is a {}", actual_weekday),
+                        )
+                        .replacement(
+                            "if the date is correct, write",
+                            weekday_text.start(),
+                            weekday_text.end(),
+                            actual_weekday,
+                        ),
+                    );
+                }
+            }
+        }
+
+        let (names, time, location) =
+            parse_details(&exam, details.as_str(), details.start(), &mut warnings);
+
+        for name in names {
+            for user in owners(&users) {
+                exams.push(ParseExam {
+                    day: exam_date,
+                    last_day,
+                    name: name.clone(),
+                    time,
+                    location: location.clone(),
+                    user,
+                    message: None,
+                });
+            }
+        }
+        previous_dates = Some((exam_date, last_day));
+    }
+
+    Ok((exams, warnings))
+}
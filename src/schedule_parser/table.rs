@@ -0,0 +1,378 @@
+// Schedules copied from university portals are tables, separated by tabs or pipes (markdown tables)
+
+use chrono::NaiveDate;
+use once_cell::sync::Lazy;
+use poise::serenity_prelude::UserId;
+use regex::Regex;
+
+use super::{
+    owners, parse_details, parse_time, resolve_date, DateText, ErrorCode, ExamTime, ParseError,
+    ParseExam, ParseOptions, Token, LOCATION_REGEX, MENTION_REGEX, TIME_REGEX,
+};
+
+// 2024-01-12
+static ISO_DATE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(\d{4})-(\d{1,2})-(\d{1,2})").unwrap());
+
+// 12 jan, 12 jan 2024, 12/01, 12/01/2024, 12.01.24
+static DATE_CELL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:(\d{1,2})\.? (\w+)|(\d{1,2})[/.\-](\d{1,2}))(?:[/.\-](\d{4}|\d{2})|\s(\d{4}))?")
+        .unwrap()
+});
+
+// |---|:---:| rows under the header of a markdown table
+static MARKDOWN_SEPARATOR_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\|?(?:\s*:?-+:?\s*\|?)+$").unwrap());
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Column {
+    Date,
+    Time,
+    EndTime,
+    Name,
+    Location,
+}
+
+// Header names for every column, checked in this order so "eindtijd" isn't a start time
+// and "examendatum" isn't a name
+const HEADERS: [(Column, &[&str]); 5] = [
+    (Column::EndTime, &["eind", "end", "tot", "fin", "until"]),
+    (
+        Column::Time,
+        &[
+            "tijd", "uur", "time", "hour", "start", "begin", "aanvang", "heure", "zeit",
+        ],
+    ),
+    (
+        Column::Date,
+        &["datum", "date", "dag", "day", "jour", "tag", "wanneer"],
+    ),
+    (
+        Column::Location,
+        &[
+            "lokaal", "room", "locatie", "location", "zaal", "plaats", "aula", "salle", "raum",
+        ],
+    ),
+    (
+        Column::Name,
+        &[
+            "vak",
+            "course",
+            "exam",
+            "opleidingsonderdeel",
+            "subject",
+            "naam",
+            "name",
+            "module",
+            "cours",
+            "fach",
+            "prüfung",
+            "titel",
+            "title",
+        ],
+    ),
+];
+
+#[derive(Clone, Debug, Default)]
+struct Columns {
+    date: Option<usize>,
+    time: Option<usize>,
+    end_time: Option<usize>,
+    name: Option<usize>,
+    location: Option<usize>,
+}
+
+impl Columns {
+    fn set(&mut self, column: Column, index: usize) {
+        let field = match column {
+            Column::Date => &mut self.date,
+            Column::Time => &mut self.time,
+            Column::EndTime => &mut self.end_time,
+            Column::Name => &mut self.name,
+            Column::Location => &mut self.location,
+        };
+        // The first matching column wins
+        field.get_or_insert(index);
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        [
+            self.date,
+            self.time,
+            self.end_time,
+            self.name,
+            self.location,
+        ]
+        .contains(&Some(index))
+    }
+}
+
+// Whether a word of a header is the name, or a compound that ends ("examendatum") or starts ("eindtijd")
+// with it. Short names only match whole words, so "duur" isn't "uur", "totaal" isn't "tot" and "weekend" isn't "end".
+fn header_word_matches(word: &str, name: &str) -> bool {
+    word == name || (name.chars().count() >= 4 && (word.ends_with(name) || word.starts_with(name)))
+}
+
+fn header_column(cell: &str) -> Option<Column> {
+    let cell = cell.to_lowercase();
+    let words: Vec<_> = cell
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    HEADERS
+        .iter()
+        .find(|(_, names)| {
+            names
+                .iter()
+                .any(|name| words.iter().any(|word| header_word_matches(word, name)))
+        })
+        .map(|(column, _)| *column)
+}
+
+// The separator used by most lines, if the schedule looks like a table
+fn detect_separator(lines: &[Token]) -> Option<char> {
+    ['\t', '|'].into_iter().find(|separator| {
+        let rows = lines
+            .iter()
+            .filter(|line| line.text.contains(*separator))
+            .count();
+        rows >= 2 && rows * 3 >= lines.len() * 2
+    })
+}
+
+// Splits a row into its cells, as tokens pointing at where the cell is in the schedule
+fn split_row(row: &Token, separator: char) -> Vec<Token> {
+    let mut text = row.text.as_str();
+    let mut offset = 0;
+    if separator == '|' {
+        // Markdown tables start and end with a pipe
+        if let Some(stripped) = text.strip_prefix('|') {
+            text = stripped;
+            offset = 1;
+        }
+        text = text.strip_suffix('|').unwrap_or(text);
+    }
+
+    let mut cells = Vec::new();
+    for cell in text.split(separator) {
+        let trimmed = cell.trim_start();
+        cells.push(Token {
+            text: trimmed.trim_end().to_owned(),
+            line_nr: row.line_nr,
+            column_nr: row.column_nr + offset + cell.len() - trimmed.len(),
+        });
+        offset += cell.len() + separator.len_utf8();
+    }
+    cells
+}
+
+// Guesses the columns from their contents when the table has no header
+fn infer_columns(rows: &[Vec<Token>]) -> Columns {
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let count = |index: usize, regex: &Regex| {
+        rows.iter()
+            .filter(|row| {
+                row.get(index)
+                    .is_some_and(|cell| regex.is_match(&cell.text))
+            })
+            .count()
+    };
+
+    let mut columns = Columns::default();
+    columns.date = (0..width)
+        .map(|i| (i, count(i, &ISO_DATE_REGEX).max(count(i, &DATE_CELL_REGEX))))
+        .filter(|(_, matches)| *matches * 2 >= rows.len())
+        .max_by_key(|(i, matches)| (*matches, std::cmp::Reverse(*i)))
+        .map(|(i, _)| i);
+    columns.time = (0..width)
+        .filter(|i| !columns.contains(*i))
+        .map(|i| (i, count(i, &TIME_REGEX)))
+        .filter(|(_, matches)| *matches * 2 >= rows.len())
+        .max_by_key(|(i, matches)| (*matches, std::cmp::Reverse(*i)))
+        .map(|(i, _)| i);
+    // The name is the first column that has text in it
+    columns.name = (0..width).find(|i| {
+        !columns.contains(*i)
+            && rows.iter().any(|row| {
+                row.get(*i)
+                    .is_some_and(|cell| cell.text.chars().any(char::is_alphabetic))
+            })
+    });
+    columns.location = (0..width).find(|i| !columns.contains(*i) && count(*i, &LOCATION_REGEX) > 0);
+
+    columns
+}
+
+fn parse_date_cell(
+    cell: &Token,
+    options: &ParseOptions,
+    warnings: &mut Vec<ParseError>,
+) -> Option<NaiveDate> {
+    if let Some(captures) = ISO_DATE_REGEX.captures(&cell.text) {
+        let date = captures.get(0).unwrap();
+        let parsed = NaiveDate::from_ymd_opt(
+            captures.get(1).unwrap().as_str().parse().ok()?,
+            captures.get(2).unwrap().as_str().parse().ok()?,
+            captures.get(3).unwrap().as_str().parse().ok()?,
+        );
+        if parsed.is_none() {
+            warnings.push(
+                ParseError::error(ErrorCode::InvalidDate, cell, "Invalid date.")
+                    .primary(date.start(), date.end()),
+            );
+        }
+        parsed
+    } else if let Some(captures) = DATE_CELL_REGEX.captures(&cell.text) {
+        let date = match (captures.get(1), captures.get(2)) {
+            (Some(day), Some(month)) => DateText::Named { day, month },
+            _ => DateText::Numeric {
+                first: captures.get(3).unwrap(),
+                second: captures.get(4).unwrap(),
+            },
+        };
+        let year = captures
+            .get(5)
+            .or(captures.get(6))
+            .and_then(|year| year.as_str().parse::<i32>().ok())
+            .map(|year| if year < 100 { year + 2000 } else { year });
+        resolve_date(cell, &date, options, options.today, year, warnings)
+    } else {
+        warnings.push(
+            ParseError::error(
+                ErrorCode::NoDateInCell,
+                cell,
+                "Could not find a date in this cell.",
+            )
+            .suggestion("write dates like 2024-01-12, 12/01/2024 or 12 jan"),
+        );
+        None
+    }
+}
+
+// Parses the schedule as a table, or returns None if it doesn't look like one
+pub(super) fn parse_table(
+    lines: &[Token],
+    options: &ParseOptions,
+    warnings: &mut Vec<ParseError>,
+) -> Option<Vec<ParseExam>> {
+    let separator = detect_separator(lines)?;
+
+    // Only reported if this turns out to be a table
+    let mut table_warnings = Vec::new();
+    let warnings_out = warnings;
+    let warnings = &mut table_warnings;
+
+    let mut header = None;
+    let mut rows = Vec::new();
+    for line in lines {
+        if !line.text.contains(separator) {
+            warnings.push(ParseError::warning(
+                ErrorCode::NotInTable,
+                line,
+                "This line is not part of the table.",
+            ));
+            continue;
+        }
+        if MARKDOWN_SEPARATOR_REGEX.is_match(&line.text) {
+            continue;
+        }
+
+        let users = MENTION_REGEX
+            .captures_iter(&line.text)
+            .filter_map(|captures| captures.get(1).unwrap().as_str().parse().ok())
+            .map(UserId)
+            .collect::<Vec<_>>();
+        let cells = split_row(line, separator);
+        // The header is the first row, if it names any columns
+        if header.is_none() && rows.is_empty() {
+            let mut columns = Columns::default();
+            for (i, cell) in cells.iter().enumerate() {
+                if let Some(column) = header_column(&cell.text) {
+                    columns.set(column, i);
+                }
+            }
+            if columns.date.is_some() {
+                header = Some(columns);
+                continue;
+            }
+        }
+        rows.push((line, users, cells));
+    }
+
+    let columns = match header {
+        Some(columns) => columns,
+        None => infer_columns(
+            &rows
+                .iter()
+                .map(|(_, _, cells)| cells.clone())
+                .collect::<Vec<_>>(),
+        ),
+    };
+    // Without dates this is just text with pipes or tabs in it
+    let date_column = columns.date?;
+
+    let mut exams = Vec::new();
+    for (line, users, row) in rows {
+        let cell = |index: Option<usize>| {
+            index
+                .and_then(|index| row.get(index))
+                .filter(|cell| !cell.text.is_empty())
+        };
+
+        let date_cell = match cell(Some(date_column)) {
+            Some(date_cell) => date_cell,
+            None => {
+                warnings.push(ParseError::warning(
+                    ErrorCode::NoDateInRow,
+                    line,
+                    "This row has no date.",
+                ));
+                continue;
+            }
+        };
+        let day = match parse_date_cell(date_cell, options, warnings) {
+            Some(day) => day,
+            None => continue,
+        };
+
+        // The name cell can still contain a time or location, like a normal line
+        let (names, mut time, mut location) = match cell(columns.name) {
+            Some(name_cell) => parse_details(name_cell, &name_cell.text, 0, warnings),
+            None => (vec![String::new()], None, None),
+        };
+
+        if let Some(time_cell) = cell(columns.time) {
+            time = parse_time(time_cell, &time_cell.text, 0, warnings).map(|(time, _)| time);
+        } else if time.is_none() {
+            // "12/01/2024 09:00"
+            time = parse_time(date_cell, &date_cell.text, 0, warnings).map(|(time, _)| time);
+        }
+        if let (Some(start), Some(end_cell)) = (time, cell(columns.end_time)) {
+            time = Some(ExamTime {
+                start: start.start,
+                end: parse_time(end_cell, &end_cell.text, 0, warnings).map(|(end, _)| end.start),
+            });
+        }
+
+        if let Some(location_cell) = cell(columns.location) {
+            location = Some(location_cell.text.to_owned());
+        }
+
+        for name in names {
+            for user in owners(&users) {
+                exams.push(ParseExam {
+                    day,
+                    last_day: None,
+                    name: name.clone(),
+                    time,
+                    location: location.clone(),
+                    user,
+                    message: None,
+                });
+            }
+        }
+    }
+
+    warnings_out.append(&mut table_warnings);
+    Some(exams)
+}
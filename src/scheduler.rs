@@ -7,27 +7,59 @@ use std::{
 use std::collections::BinaryHeap;
 
 use crate::{
-    database::{Database, DbExam, DbGuild},
-    formatter::format_exam,
+    countdown,
+    database::{Database, DbDayThread, DbExam, DbGuild, DbSession},
+    followup::{create_buttons, default_followup_format},
+    formatter::{
+        choose_message, default_embed_title, default_format, format_exam, mention_owner, Delivery,
+        Variables, DISCORD_MESSAGE_LENGTH, MAX_MENTIONS_LENGTH,
+    },
+    i18n::{self, tr, Language},
     Error,
 };
 use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 use chrono_tz::Tz;
 use log::{debug, error, info, warn};
-use poise::serenity_prelude::Mentionable;
+use poise::serenity_prelude::{ChannelId, GuildId, Member, Mentionable, RoleId, UserId};
 use serenity::client::Context;
 use tokio::time::{self, MissedTickBehavior};
 
+// How often live countdown messages are edited
+const COUNTDOWN_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+// Data of guilds the bot was removed from is kept this long, in case it's added back
+const LEFT_GUILD_DAYS: i64 = 7;
+// Day threads are kept this long after their day, so follow-ups sent late after downtime still find them
+const DAY_THREAD_DAYS: i64 = 3;
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// Ends the list of exams above a day thread once no more fit in the message
+const DAY_THREAD_MORE: &str = "\n\t…";
+
+// Most members Discord returns per request
+const MEMBER_PAGE: u64 = 1000;
+
 // Schedules when an alarm is played
 pub struct Scheduler {
     database: Database,
     exams: Mutex<BinaryHeap<ScheduledExam>>,
+    sessions: Mutex<BinaryHeap<ScheduledSession>>,
+    // Held while finding or creating the thread of a day, so each day gets only one
+    day_threads: tokio::sync::Mutex<()>,
     bot_context: Context,
 }
 
+// What gets sent for an exam
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+enum ScheduledMessage {
+    Reminder,
+    FollowUp,
+}
+
 #[derive(Clone, Eq, PartialEq)]
 pub struct ScheduledExam {
     scheduled_time: chrono::DateTime<Utc>,
+    message: ScheduledMessage,
     exam: DbExam,
     guild: DbGuild,
 }
@@ -44,6 +76,7 @@ impl Ord for ScheduledExam {
             .scheduled_time
             .cmp(&self.scheduled_time)
             .then_with(|| self.exam.exam_id.cmp(&other.exam.exam_id))
+            .then_with(|| self.message.cmp(&other.message))
     }
 }
 
@@ -54,6 +87,37 @@ impl PartialOrd for ScheduledExam {
     }
 }
 
+// What gets announced for a session
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+enum SessionAnnouncement {
+    Start,
+    End,
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct ScheduledSession {
+    scheduled_time: chrono::DateTime<Utc>,
+    announcement: SessionAnnouncement,
+    session: DbSession,
+}
+
+// Min-heap, like `ScheduledExam`
+impl Ord for ScheduledSession {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .scheduled_time
+            .cmp(&self.scheduled_time)
+            .then_with(|| self.session.session_id.cmp(&other.session.session_id))
+            .then_with(|| self.announcement.cmp(&other.announcement))
+    }
+}
+
+impl PartialOrd for ScheduledSession {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 async fn schedule_task(scheduler: Arc<Scheduler>) {
     info!("Starting scheduler task");
     // Load exams
@@ -75,7 +139,7 @@ async fn schedule_task(scheduler: Arc<Scheduler>) {
 
             while exams
                 .peek()
-                .and_then(|exam| Some(exam.scheduled_time <= now))
+                .map(|exam| exam.scheduled_time <= now)
                 .unwrap_or(false)
             {
                 // Send the exam message
@@ -83,16 +147,42 @@ async fn schedule_task(scheduler: Arc<Scheduler>) {
 
                 let scheduler_clone = scheduler.clone();
                 tokio::spawn(async move {
-                    match scheduler_clone.send_message(exam.exam.exam_id).await {
-                        Ok(_) => {
-                            let _ = scheduler_clone
-                                .database
-                                .delete_exam(exam.exam.exam_id)
-                                .await;
+                    let exam_id = exam.exam.exam_id;
+                    let result = match exam.message {
+                        ScheduledMessage::Reminder => {
+                            match scheduler_clone.send_message(exam_id).await {
+                                Ok(_) => scheduler_clone.finish_reminder(exam_id).await,
+                                err => err,
+                            }
                         }
-                        Err(err) => {
-                            error!("Error while sending message: {}", err);
+                        ScheduledMessage::FollowUp => {
+                            match scheduler_clone.send_followup(exam_id).await {
+                                Ok(_) => scheduler_clone.database.finish_exam(exam_id).await,
+                                err => err,
+                            }
                         }
+                    };
+                    if let Err(err) = result {
+                        error!("Error while sending message: {}", err);
+                    }
+                });
+            }
+
+            let mut sessions = scheduler.sessions.lock().unwrap();
+            while sessions
+                .peek()
+                .map(|session| session.scheduled_time <= now)
+                .unwrap_or(false)
+            {
+                let session = sessions.pop().unwrap();
+
+                let scheduler_clone = scheduler.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = scheduler_clone
+                        .send_session_announcement(session.session.session_id, session.announcement)
+                        .await
+                    {
+                        error!("Error while sending session announcement: {}", err);
                     }
                 });
             }
@@ -102,12 +192,66 @@ async fn schedule_task(scheduler: Arc<Scheduler>) {
     }
 }
 
-fn calculate_schedule_time(
+async fn countdown_task(scheduler: Arc<Scheduler>) {
+    let mut interval = time::interval(COUNTDOWN_INTERVAL);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        interval.tick().await;
+
+        let guilds = match scheduler.database.get_countdown_guilds().await {
+            Ok(guilds) => guilds,
+            Err(err) => {
+                error!("Error while loading countdown guilds: {}", err);
+                continue;
+            }
+        };
+        for guild_id in guilds {
+            if let Err(err) = scheduler.update_countdown(guild_id).await {
+                error!("Error while updating countdown in {}: {}", guild_id, err);
+            }
+        }
+    }
+}
+
+async fn cleanup_task(scheduler: Arc<Scheduler>) {
+    let mut interval = time::interval(CLEANUP_INTERVAL);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        interval.tick().await;
+
+        let before = Utc::now() - chrono::Duration::days(LEFT_GUILD_DAYS);
+        match scheduler.database.delete_left_guilds(before).await {
+            Ok(0) => {}
+            Ok(deleted) => info!("Deleted the data of {} guilds the bot left", deleted),
+            Err(err) => error!("Error while deleting left guilds: {}", err),
+        }
+
+        // Yesterday in UTC has passed in every timezone
+        let yesterday = Utc::now().date_naive() - chrono::Duration::days(1);
+        if let Err(err) = scheduler.database.delete_finished_exams(yesterday).await {
+            error!("Error while deleting finished exams: {}", err);
+        }
+
+        // Follow-ups are sent on the exam day, the last thing sent in its thread
+        let day_threads_before = Utc::now().date_naive() - chrono::Duration::days(DAY_THREAD_DAYS);
+        if let Err(err) = scheduler
+            .database
+            .delete_day_threads_before(day_threads_before)
+            .await
+        {
+            error!("Error while deleting old day threads: {}", err);
+        }
+    }
+}
+
+pub(crate) fn calculate_schedule_time(
     date: NaiveDate,
     time: NaiveTime,
     timezone: chrono_tz::Tz,
 ) -> DateTime<Utc> {
-    let naive_datetime = NaiveDateTime::new(date, time) - Days::new(1);
+    let naive_datetime = NaiveDateTime::new(date, time);
     let offset = match timezone.offset_from_local_datetime(&naive_datetime) {
         chrono::LocalResult::None => timezone.offset_from_utc_datetime(&naive_datetime),
         chrono::LocalResult::Single(offset) => offset,
@@ -118,15 +262,115 @@ fn calculate_schedule_time(
     datetime_local.with_timezone(&Utc)
 }
 
+// Role exams mention the role when that pings its members, who can be far more than fit in a few
+// messages. Otherwise every member is mentioned, like the participants of a group exam.
+fn exam_mentions(exam: &DbExam, users: &[UserId], role_mentionable: bool) -> Vec<String> {
+    match exam.role {
+        Some(role_id) if role_mentionable && !users.is_empty() => {
+            vec![role_id.mention().to_string()]
+        }
+        _ => mention_chunks(users),
+    }
+}
+
+// Mentions split over as many messages as needed, the first one short enough for {user}
+fn mention_chunks(users: &[UserId]) -> Vec<String> {
+    let mut chunks: Vec<String> = Vec::new();
+    for user_id in users {
+        let mention = user_id.mention().to_string();
+        let limit = if chunks.len() <= 1 {
+            MAX_MENTIONS_LENGTH
+        } else {
+            DISCORD_MESSAGE_LENGTH
+        };
+        match chunks.last_mut() {
+            Some(chunk) if chunk.len() + " ".len() + mention.len() <= limit => {
+                chunk.push(' ');
+                chunk.push_str(&mention);
+            }
+            _ => chunks.push(mention),
+        }
+    }
+    chunks
+}
+
+// Reminders are sent the day before the exam, follow-ups on the day itself
+fn schedule_exam(exam: DbExam, guild: DbGuild) -> Vec<ScheduledExam> {
+    let mut scheduled = Vec::new();
+    if !exam.reminded {
+        scheduled.push(ScheduledExam {
+            scheduled_time: calculate_schedule_time(
+                exam.day - Days::new(1),
+                guild.message_time,
+                guild.message_timezone,
+            ),
+            message: ScheduledMessage::Reminder,
+            exam: exam.clone(),
+            guild: guild.clone(),
+        });
+    }
+    // Reminded exams are kept for their follow-up, even when follow-ups were turned off since
+    if guild.followup_time.is_some() || exam.reminded {
+        scheduled.push(ScheduledExam {
+            scheduled_time: calculate_schedule_time(
+                exam.day,
+                guild.followup_time.unwrap_or(guild.message_time),
+                guild.message_timezone,
+            ),
+            message: ScheduledMessage::FollowUp,
+            exam,
+            guild,
+        });
+    }
+    scheduled
+}
+
+// The start is announced the day before the session, the end the day after it
+fn schedule_session(session: DbSession, guild: DbGuild) -> Vec<ScheduledSession> {
+    let today = Utc::now()
+        .with_timezone(&guild.message_timezone)
+        .date_naive();
+    let mut scheduled = Vec::new();
+    // Sessions added once they started don't start tomorrow anymore
+    if !session.start_announced && today < session.start_day {
+        scheduled.push(ScheduledSession {
+            scheduled_time: calculate_schedule_time(
+                session.start_day - Days::new(1),
+                guild.message_time,
+                guild.message_timezone,
+            ),
+            announcement: SessionAnnouncement::Start,
+            session: session.clone(),
+        });
+    }
+    let announce_end = session.end_day + Days::new(1);
+    if !session.end_announced && today <= announce_end {
+        scheduled.push(ScheduledSession {
+            scheduled_time: calculate_schedule_time(
+                announce_end,
+                guild.message_time,
+                guild.message_timezone,
+            ),
+            announcement: SessionAnnouncement::End,
+            session,
+        });
+    }
+    scheduled
+}
+
 impl Scheduler {
     pub fn new(database: Database, ctx: Context) -> Arc<Self> {
         let scheduler = Arc::new(Scheduler {
             database,
             exams: Mutex::new(BinaryHeap::new()),
+            sessions: Mutex::new(BinaryHeap::new()),
+            day_threads: tokio::sync::Mutex::new(()),
             bot_context: ctx,
         });
 
         tokio::spawn(schedule_task(scheduler.clone()));
+        tokio::spawn(countdown_task(scheduler.clone()));
+        tokio::spawn(cleanup_task(scheduler.clone()));
 
         scheduler
     }
@@ -135,17 +379,69 @@ impl Scheduler {
         if let Some(exam) = self.database.get_exam(exam_id).await? {
             if let Some(guild) = self.database.get_guild(exam.guild_id).await? {
                 debug!("Sending exam message in {}", guild.guild_id);
-                guild
-                    .message_channel_id
-                    .say(
-                        &self.bot_context,
-                        format!(
-                            "{}\n{}",
-                            exam.user_id.mention(),
-                            format_exam(guild.format, exam)
-                        ),
-                    )
+                let today = Utc::now()
+                    .with_timezone(&guild.message_timezone)
+                    .date_naive();
+                let guild_name = guild
+                    .guild_id
+                    .name(&self.bot_context.cache)
+                    .unwrap_or_default();
+                let language = i18n::guild_language(&guild, &self.bot_context.cache);
+                let mut variables = Variables::new(&exam, today, &guild_name, language);
+
+                // Group and role exams get one reminder for everyone taking them,
+                // with the mentions that don't fit in it in extra messages
+                let users = self.exam_users(&exam).await?;
+                let role_mentionable = self.role_mentionable(&exam).await;
+                let mut mentions = exam_mentions(&exam, &users, role_mentionable).into_iter();
+                let mention = if let Some(mention) = mentions.next() {
+                    mention
+                } else {
+                    debug!(
+                        "Not sending exam message: exam {} has no participants",
+                        exam_id
+                    );
+                    return Ok(());
+                };
+                variables.user = mention.clone();
+
+                // The exam's own message, a random message from the guild's pool,
+                // or the guild's format if it has none
+                let messages = self.database.get_guild_messages(guild.guild_id).await?;
+                let last_message_id = self
+                    .database
+                    .get_last_message(guild.guild_id, exam.user_id)
                     .await?;
+                let message = choose_message(&messages, &exam, last_message_id);
+                let format = match (&exam.message, message) {
+                    (Some(format), _) => format,
+                    (None, Some(message)) => &message.format,
+                    (None, None) => guild.format.as_deref().unwrap_or(default_format(language)),
+                };
+
+                let text = format_exam(format, &variables);
+                let channel_id = self
+                    .reminder_channel(&guild, &exam, &variables, language)
+                    .await;
+                match guild.delivery {
+                    Delivery::Text => {
+                        channel_id
+                            .say(&self.bot_context, format!("{}\n{}", mention, text))
+                            .await?;
+                    }
+                    Delivery::Embed => {
+                        self.send_embed(&guild, &exam, channel_id, &variables, language, text)
+                            .await?
+                    }
+                }
+                for mention in mentions {
+                    channel_id.say(&self.bot_context, mention).await?;
+                }
+                if let Some(message) = message {
+                    self.database
+                        .set_last_message(guild.guild_id, exam.user_id, message.message_id)
+                        .await?;
+                }
                 return Ok(());
             } else {
                 warn!(
@@ -163,6 +459,400 @@ impl Scheduler {
         Ok(())
     }
 
+    // The thread of the exam's day if the guild uses those, the reminder channel otherwise
+    async fn reminder_channel(
+        &self,
+        guild: &DbGuild,
+        exam: &DbExam,
+        variables: &Variables,
+        language: Language,
+    ) -> ChannelId {
+        if !guild.day_threads {
+            return guild.message_channel_id;
+        }
+        match self.day_thread(guild, exam, variables, language).await {
+            Ok(thread_id) => thread_id,
+            Err(err) => {
+                warn!(
+                    "Couldn't use a thread for {} in {}, sending in the channel: {}",
+                    exam.day, guild.guild_id, err
+                );
+                guild.message_channel_id
+            }
+        }
+    }
+
+    // Adds the exam to the message of its day, or sends one with a new thread under it
+    async fn day_thread(
+        &self,
+        guild: &DbGuild,
+        exam: &DbExam,
+        variables: &Variables,
+        language: Language,
+    ) -> Result<ChannelId, Error> {
+        let _lock = self.day_threads.lock().await;
+
+        let owner = mention_owner(exam, language);
+        let line = if exam.exam_name.is_empty() {
+            format!("\t{}", owner)
+        } else {
+            format!("\t{} - {}", owner, exam.exam_name)
+        };
+
+        match self
+            .database
+            .get_day_thread(guild.guild_id, exam.day)
+            .await?
+        {
+            // A message in another channel is from before the channel was changed
+            Some(day_thread) if day_thread.channel_id == guild.message_channel_id => {
+                let mut message = day_thread
+                    .channel_id
+                    .message(&self.bot_context, day_thread.message_id)
+                    .await?;
+                // The thread has every reminder, the message lists as many exams as fit
+                let content = format!("{}\n{}", message.content, line);
+                if content.chars().count() + DAY_THREAD_MORE.chars().count()
+                    <= DISCORD_MESSAGE_LENGTH
+                {
+                    message
+                        .edit(&self.bot_context, |m| m.content(content))
+                        .await?;
+                } else if !message.content.ends_with(DAY_THREAD_MORE) {
+                    let content = format!("{}{}", message.content, DAY_THREAD_MORE);
+                    message
+                        .edit(&self.bot_context, |m| m.content(content))
+                        .await?;
+                }
+                Ok(day_thread.thread_id)
+            }
+            _ => {
+                let header = tr!(
+                    language,
+                    "day-thread-header",
+                    weekday = variables.weekday,
+                    day = exam.day
+                );
+                // The reminders in the thread ping everyone already
+                let message = guild
+                    .message_channel_id
+                    .send_message(&self.bot_context, |m| {
+                        m.content(format!("{}\n{}", header, line))
+                            .allowed_mentions(|mentions| mentions.empty_parse())
+                    })
+                    .await?;
+                let thread = guild
+                    .message_channel_id
+                    .create_public_thread(&self.bot_context, message.id, |t| {
+                        t.name(tr!(
+                            language,
+                            "day-thread-name",
+                            weekday = variables.weekday,
+                            day = exam.day
+                        ))
+                    })
+                    .await?;
+                self.database
+                    .set_day_thread(DbDayThread {
+                        guild_id: guild.guild_id,
+                        day: exam.day,
+                        channel_id: guild.message_channel_id,
+                        message_id: message.id,
+                        thread_id: thread.id,
+                    })
+                    .await?;
+                Ok(thread.id)
+            }
+        }
+    }
+
+    // Follow-ups go in the thread the reminder was sent in, if there is one
+    async fn followup_channel(&self, guild: &DbGuild, exam: &DbExam) -> Result<ChannelId, Error> {
+        if guild.day_threads {
+            if let Some(day_thread) = self
+                .database
+                .get_day_thread(guild.guild_id, exam.day)
+                .await?
+            {
+                if day_thread.channel_id == guild.message_channel_id {
+                    return Ok(day_thread.thread_id);
+                }
+            }
+        }
+        Ok(guild.message_channel_id)
+    }
+
+    // Mentions everyone in `variables.user`
+    async fn send_embed(
+        &self,
+        guild: &DbGuild,
+        exam: &DbExam,
+        channel_id: ChannelId,
+        variables: &Variables,
+        language: Language,
+        text: String,
+    ) -> Result<(), Error> {
+        let title = format_exam(
+            guild
+                .embed_title
+                .as_deref()
+                .unwrap_or(default_embed_title(language)),
+            variables,
+        );
+        let footer = match exam.time {
+            Some(time) => tr!(
+                language,
+                "embed-footer-time",
+                day = exam.day,
+                time = time.format("%H:%M")
+            ),
+            None => tr!(language, "embed-footer", day = exam.day),
+        };
+
+        // Group and role exams have no single user to list the exams of
+        let other_exams = if exam.group || exam.role.is_some() {
+            Vec::new()
+        } else {
+            self.database
+                .get_user_exams(exam.guild_id, exam.user_id)
+                .await?
+        };
+        let mut upcoming: Vec<_> = other_exams
+            .into_iter()
+            .filter(|other| other.exam_id != exam.exam_id && other.day >= exam.day)
+            .collect();
+        upcoming.sort_unstable_by_key(|other| other.day);
+        let upcoming = upcoming
+            .iter()
+            .take(5)
+            .map(|other| {
+                if other.exam_name.is_empty() {
+                    format!("{}", other.day)
+                } else {
+                    format!("{} - {}", other.day, other.exam_name)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        channel_id
+            .send_message(&self.bot_context, |m| {
+                // The mention has to be in the content, mentions in embeds don't ping
+                m.content(&variables.user).embed(|e| {
+                    e.title(title).description(text).footer(|f| f.text(footer));
+                    if let Some(colour) = guild.embed_colour {
+                        e.colour(colour);
+                    }
+                    if let Some(thumbnail) = &guild.embed_thumbnail {
+                        e.thumbnail(thumbnail);
+                    }
+                    if let Some(image) = &guild.embed_image {
+                        e.image(image);
+                    }
+                    if !upcoming.is_empty() {
+                        e.field(tr!(language, "embed-upcoming"), upcoming, false);
+                    }
+                    e
+                })
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    // Everyone who takes the exam, role members are looked up when this is called
+    async fn exam_users(&self, exam: &DbExam) -> Result<Vec<UserId>, Error> {
+        match exam.role {
+            Some(role_id) => self.role_members(exam.guild_id, role_id).await,
+            None => self.database.get_exam_users(exam).await,
+        }
+    }
+
+    // Discord only pings a role's members if the role is mentionable or the bot may mention all roles
+    async fn role_mentionable(&self, exam: &DbExam) -> bool {
+        let role_id = match exam.role {
+            Some(role_id) => role_id,
+            None => return false,
+        };
+        let guild = match self.bot_context.cache.guild(exam.guild_id) {
+            Some(guild) => guild,
+            None => return false,
+        };
+        if guild
+            .roles
+            .get(&role_id)
+            .is_some_and(|role| role.mentionable)
+        {
+            return true;
+        }
+        guild
+            .member_permissions(&self.bot_context, self.bot_context.cache.current_user_id())
+            .await
+            .is_ok_and(|permissions| permissions.mention_everyone())
+    }
+
+    // Uses the cache if it has every member of the guild, the member list otherwise
+    async fn role_members(&self, guild_id: GuildId, role_id: RoleId) -> Result<Vec<UserId>, Error> {
+        let has_role = |member: &Member| !member.user.bot && member.roles.contains(&role_id);
+
+        let cached = self
+            .bot_context
+            .cache
+            .guild_field(guild_id, |guild| {
+                (guild.members.len() as u64 == guild.member_count).then(|| {
+                    guild
+                        .members
+                        .values()
+                        .filter(|member| has_role(member))
+                        .map(|member| member.user.id)
+                        .collect::<Vec<_>>()
+                })
+            })
+            .flatten();
+        if let Some(users) = cached {
+            return Ok(users);
+        }
+
+        let mut users = Vec::new();
+        let mut after = None;
+        loop {
+            let members = guild_id
+                .members(&self.bot_context.http, Some(MEMBER_PAGE), after)
+                .await?;
+            users.extend(
+                members
+                    .iter()
+                    .filter(|member| has_role(member))
+                    .map(|member| member.user.id),
+            );
+            match members.last() {
+                Some(last) if members.len() as u64 == MEMBER_PAGE => after = Some(last.user.id),
+                _ => break,
+            }
+        }
+
+        Ok(users)
+    }
+
+    // Exams are kept until their follow-up, if the guild sends those
+    async fn finish_reminder(&self, exam_id: i64) -> Result<(), Error> {
+        let followups = match self.database.get_exam(exam_id).await? {
+            Some(exam) => self
+                .database
+                .get_guild(exam.guild_id)
+                .await?
+                .is_some_and(|guild| guild.followup_time.is_some()),
+            None => false,
+        };
+        if followups {
+            self.database.set_exam_reminded(exam_id).await
+        } else {
+            self.database.finish_exam(exam_id).await
+        }
+    }
+
+    pub async fn update_countdown(&self, guild_id: GuildId) -> Result<(), Error> {
+        countdown::update_message(&self.bot_context, &self.database, guild_id).await
+    }
+
+    async fn send_session_announcement(
+        &self,
+        session_id: i64,
+        announcement: SessionAnnouncement,
+    ) -> Result<(), Error> {
+        debug!("Attempting to send session announcement...");
+        let session = if let Some(session) = self.database.get_session(session_id).await? {
+            session
+        } else {
+            // Deleted since it was scheduled
+            return Ok(());
+        };
+        let guild = if let Some(guild) = self.database.get_guild(session.guild_id).await? {
+            guild
+        } else {
+            return Ok(());
+        };
+        let language = i18n::guild_language(&guild, &self.bot_context.cache);
+
+        let text = match announcement {
+            SessionAnnouncement::Start => {
+                let (_, total) = self.database.count_session_exams(session_id).await?;
+                tr!(
+                    language,
+                    "session-starts",
+                    session = session.name,
+                    count = total
+                )
+            }
+            SessionAnnouncement::End => tr!(language, "session-over", session = session.name),
+        };
+        guild
+            .message_channel_id
+            .say(&self.bot_context, text)
+            .await?;
+
+        match announcement {
+            SessionAnnouncement::Start => {
+                self.database.set_session_start_announced(session_id).await
+            }
+            SessionAnnouncement::End => self.database.set_session_end_announced(session_id).await,
+        }
+    }
+
+    async fn send_followup(&self, exam_id: i64) -> Result<(), Error> {
+        debug!("Attempting to send follow-up message...");
+        if let Some(exam) = self.database.get_exam(exam_id).await? {
+            if let Some(guild) = self.database.get_guild(exam.guild_id).await? {
+                if guild.followup_time.is_none() {
+                    // Turned off after the reminder was sent
+                    return Ok(());
+                }
+                debug!("Sending follow-up message in {}", guild.guild_id);
+                let today = Utc::now()
+                    .with_timezone(&guild.message_timezone)
+                    .date_naive();
+                let guild_name = guild
+                    .guild_id
+                    .name(&self.bot_context.cache)
+                    .unwrap_or_default();
+                let language = i18n::guild_language(&guild, &self.bot_context.cache);
+                let mut variables = Variables::new(&exam, today, &guild_name, language);
+                let users = self.exam_users(&exam).await?;
+                let role_mentionable = self.role_mentionable(&exam).await;
+                let mut mentions = exam_mentions(&exam, &users, role_mentionable).into_iter();
+                let mention = if let Some(mention) = mentions.next() {
+                    mention
+                } else {
+                    return Ok(());
+                };
+                variables.user = mention.clone();
+                let text = format_exam(
+                    guild
+                        .followup_format
+                        .as_deref()
+                        .unwrap_or(default_followup_format(language)),
+                    &variables,
+                );
+
+                for user_id in users {
+                    self.database.insert_exam_result(&exam, user_id).await?;
+                }
+                let channel_id = self.followup_channel(&guild, &exam).await?;
+                channel_id
+                    .send_message(&self.bot_context, |m| {
+                        m.content(format!("{}\n{}", mention, text))
+                            .components(|c| create_buttons(c, exam.exam_id))
+                    })
+                    .await?;
+                for mention in mentions {
+                    channel_id.say(&self.bot_context, mention).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn load_exams_from_database(&self) -> Result<(), Error> {
         let exams_database = self.database.get_all_exams().await?;
 
@@ -171,17 +861,7 @@ impl Scheduler {
         for exam_database in exams_database {
             // Calculate scheduled time
             if let Some(guild_database) = self.database.get_guild(exam_database.guild_id).await? {
-                let datetime_utc: DateTime<Utc> = calculate_schedule_time(
-                    exam_database.day,
-                    guild_database.message_time,
-                    guild_database.message_timezone,
-                );
-                let exam = ScheduledExam {
-                    scheduled_time: datetime_utc,
-                    exam: exam_database,
-                    guild: guild_database,
-                };
-                exams_vec.push(exam);
+                exams_vec.extend(schedule_exam(exam_database, guild_database));
             }
         }
 
@@ -193,6 +873,33 @@ impl Scheduler {
             }
         }
 
+        // Session announcements depend on the same guild settings
+        self.load_sessions_from_database().await
+    }
+
+    pub async fn load_sessions_from_database(&self) -> Result<(), Error> {
+        let sessions_database = self.database.get_all_sessions().await?;
+
+        debug!(
+            "(Re)loading {} sessions from database",
+            sessions_database.len()
+        );
+        let mut sessions_vec = Vec::new();
+        for session_database in sessions_database {
+            if let Some(guild_database) = self.database.get_guild(session_database.guild_id).await?
+            {
+                sessions_vec.extend(schedule_session(session_database, guild_database));
+            }
+        }
+
+        {
+            let mut sessions = self.sessions.lock().map_err(|_| "Error locking Mutex")?;
+            sessions.clear();
+            for session in sessions_vec {
+                sessions.push(session)
+            }
+        }
+
         Ok(())
     }
 
@@ -201,23 +908,77 @@ impl Scheduler {
         if let Some(exam_database) = self.database.get_exam(exam_id).await? {
             if let Some(guild_database) = self.database.get_guild(exam_database.guild_id).await? {
                 debug!("Adding exam to scheduler: {:?}", exam_database);
-                let datetime_utc: DateTime<Utc> = calculate_schedule_time(
-                    exam_database.day,
-                    guild_database.message_time,
-                    guild_database.message_timezone,
-                );
-                let exam = ScheduledExam {
-                    scheduled_time: datetime_utc,
-                    exam: exam_database,
-                    guild: guild_database,
-                };
                 {
                     let mut exams = self.exams.lock().map_err(|_| "Error locking Mutex")?;
-                    exams.push(exam);
+                    exams.extend(schedule_exam(exam_database, guild_database));
                 }
             }
         }
 
         Ok(())
     }
+
+    // Takes the user's own exams out of the schedule, without touching reminders that are being sent
+    pub fn remove_user_exams(&self, guild_id: GuildId, user_id: UserId) -> Result<(), Error> {
+        let mut exams = self.exams.lock().map_err(|_| "Error locking Mutex")?;
+        exams.retain(|scheduled| {
+            let exam = &scheduled.exam;
+            !(exam.guild_id == guild_id
+                && exam.user_id == user_id
+                && !exam.group
+                && exam.role.is_none())
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role_exam() -> DbExam {
+        DbExam {
+            exam_id: 1,
+            user_id: UserId(10),
+            guild_id: GuildId(20),
+            day: NaiveDate::from_ymd_opt(2025, 1, 13).unwrap(),
+            exam_name: "Algebra".to_owned(),
+            time: None,
+            message: None,
+            reminded: false,
+            group: false,
+            role: Some(RoleId(30)),
+        }
+    }
+
+    #[test]
+    fn mentionable_role_is_mentioned() {
+        let users = [UserId(1), UserId(2)];
+        assert_eq!(exam_mentions(&role_exam(), &users, true), ["<@&30>"]);
+    }
+
+    #[test]
+    fn unmentionable_role_mentions_its_members() {
+        let users = [UserId(1), UserId(2)];
+        assert_eq!(exam_mentions(&role_exam(), &users, false), ["<@1> <@2>"]);
+    }
+
+    #[test]
+    fn role_without_members_is_not_mentioned() {
+        assert!(exam_mentions(&role_exam(), &[], true).is_empty());
+    }
+
+    #[test]
+    fn mentions_are_split() {
+        let users: Vec<_> = (0..1000)
+            .map(|i| UserId(100_000_000_000_000_000 + i))
+            .collect();
+        let chunks = mention_chunks(&users);
+        assert!(chunks.len() > 2);
+        assert!(chunks[0].len() <= MAX_MENTIONS_LENGTH);
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk.len() <= DISCORD_MESSAGE_LENGTH));
+        assert_eq!(chunks.join(" ").split(' ').count(), users.len());
+    }
 }
@@ -0,0 +1,64 @@
+// Regression tests for the schedule parser.
+// Every tests/fixtures/schedules/<name>.txt is a schedule message, <name>.json is the expected output
+// of `parse-schedule --json` for it, including the options it was parsed with.
+// Create or update one with: cargo run --bin parse-schedule -- --json --today 2024-12-01 <name>.txt > <name>.json
+
+use std::{fs, path::Path};
+
+use chrono::NaiveDate;
+use hanne_is_leuk_bot::{
+    locale,
+    schedule_parser::{self, ParseOptions},
+};
+use serde_json::Value;
+
+fn parse_fixture(schedule: &str, expected: &Value) -> Value {
+    let mut options = ParseOptions::new(
+        NaiveDate::parse_from_str(expected["today"].as_str().unwrap(), "%Y-%m-%d").unwrap(),
+    );
+    options.locales = locale::parse_locales(expected["locales"].as_str().unwrap()).unwrap();
+    options.date_order = expected["date_order"].as_str().unwrap().parse().unwrap();
+
+    let (exams, warnings) = schedule_parser::parse(schedule, &options).unwrap();
+    serde_json::json!({
+        "today": options.today,
+        "locales": locale::format_locales(&options.locales),
+        "date_order": options.date_order.code(),
+        "exams": exams,
+        "diagnostics": warnings,
+    })
+}
+
+#[test]
+fn schedule_fixtures() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/schedules");
+    let mut schedules: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "txt"))
+        .collect();
+    schedules.sort();
+    assert!(!schedules.is_empty(), "No fixtures in {}", dir.display());
+
+    let mut failed = Vec::new();
+    for schedule_path in schedules {
+        let expected_path = schedule_path.with_extension("json");
+        let expected: Value = match fs::read_to_string(&expected_path) {
+            Ok(expected) => serde_json::from_str(&expected).unwrap(),
+            Err(_) => panic!("Missing expected output {}", expected_path.display()),
+        };
+        let schedule = fs::read_to_string(&schedule_path).unwrap();
+
+        let actual = parse_fixture(&schedule, &expected);
+        if actual != expected {
+            eprintln!(
+                "{} changed, got:\n{}",
+                schedule_path.display(),
+                serde_json::to_string_pretty(&actual).unwrap()
+            );
+            failed.push(schedule_path.display().to_string());
+        }
+    }
+
+    assert!(failed.is_empty(), "Fixtures failed: {}", failed.join(", "));
+}